@@ -7,9 +7,7 @@ use std::{
 use zbars::prelude::*;
 
 pub fn main() {
-
-    // TODO: Image must be created after Processor::init. Investigate!
-//    let mut image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+    let image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
 
     let processor = ZBarProcessor::builder()
         .threaded(true)
@@ -23,13 +21,10 @@ pub fn main() {
     // initialize video (system dependent!)
     processor.init("/dev/video0", true).unwrap();
 
-    // TODO: Image must be created after Processor::init. Investigate!
-    let mut image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
-
     // set processor visible in order display the image to process
     processor.set_visible(true).unwrap();
 
-    let symbols = processor.process_image(&mut image).unwrap();
+    let symbols = processor.process_image(&image).unwrap();
     println!("{}", symbols.first_symbol().unwrap().data());
 
     // display image for 2 seconds
@@ -1,6 +1,9 @@
 extern crate zbars;
 
-use zbars::prelude::*;
+use zbars::{
+    prelude::*,
+    processor::IoMode,
+};
 
 pub fn main() {
     let processor = ZBarProcessor::builder()
@@ -14,7 +17,7 @@ pub fn main() {
         .build()
         .unwrap();
 
-    processor.request_iomode(0).unwrap();
+    processor.request_iomode_typed(IoMode::Auto).unwrap();
 
     // initialize video (system dependent!)
     processor.init("/dev/video0", true).unwrap();
@@ -0,0 +1,77 @@
+use ZBarConfig;
+
+/// A typed `ZBarConfig` together with its value.
+///
+/// Boolean configs take a `bool` and numeric configs an `i32`, so nonsensical combinations
+/// like enabling a symbology with a value of `57` can't be expressed.
+///
+/// # Examples
+///
+/// ```
+/// use zbars::config::Config;
+/// use zbars::prelude::*;
+///
+/// let scanner = ZBarImageScanner::new();
+/// scanner.set_config_typed(ZBarSymbolType::ZBAR_QRCODE, Config::Enable(true)).unwrap();
+/// scanner.set_config_typed(ZBarSymbolType::ZBAR_CODE128, Config::MinLen(4)).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Config {
+    /// Enables or disables a symbology.
+    Enable(bool),
+    /// Verifies an optional check digit.
+    AddCheck(bool),
+    /// Returns the check digit as part of the decoded data.
+    EmitCheck(bool),
+    /// Enables full ASCII character set.
+    Ascii(bool),
+    /// Minimum data length for a valid decode.
+    MinLen(i32),
+    /// Maximum data length for a valid decode.
+    MaxLen(i32),
+    /// Enables the collection of position data.
+    Position(bool),
+    /// Image scanner horizontal scan density.
+    XDensity(i32),
+    /// Image scanner vertical scan density.
+    YDensity(i32),
+}
+impl Config {
+    /// Converts this `Config` into the raw `ZBarConfig` and value passed to ZBar.
+    pub fn into_raw(self) -> (ZBarConfig, i32) {
+        match self {
+            Config::Enable(v)    => (ZBarConfig::ZBAR_CFG_ENABLE, v as i32),
+            Config::AddCheck(v)  => (ZBarConfig::ZBAR_CFG_ADD_CHECK, v as i32),
+            Config::EmitCheck(v) => (ZBarConfig::ZBAR_CFG_EMIT_CHECK, v as i32),
+            Config::Ascii(v)     => (ZBarConfig::ZBAR_CFG_ASCII, v as i32),
+            Config::MinLen(v)    => (ZBarConfig::ZBAR_CFG_MIN_LEN, v),
+            Config::MaxLen(v)    => (ZBarConfig::ZBAR_CFG_MAX_LEN, v),
+            Config::Position(v)  => (ZBarConfig::ZBAR_CFG_POSITION, v as i32),
+            Config::XDensity(v)  => (ZBarConfig::ZBAR_CFG_X_DENSITY, v),
+            Config::YDensity(v)  => (ZBarConfig::ZBAR_CFG_Y_DENSITY, v),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_into_raw_bool() {
+        assert_eq!(Config::Enable(true).into_raw(), (ZBarConfig::ZBAR_CFG_ENABLE, 1));
+        assert_eq!(Config::Enable(false).into_raw(), (ZBarConfig::ZBAR_CFG_ENABLE, 0));
+        assert_eq!(Config::AddCheck(true).into_raw(), (ZBarConfig::ZBAR_CFG_ADD_CHECK, 1));
+        assert_eq!(Config::EmitCheck(false).into_raw(), (ZBarConfig::ZBAR_CFG_EMIT_CHECK, 0));
+        assert_eq!(Config::Ascii(true).into_raw(), (ZBarConfig::ZBAR_CFG_ASCII, 1));
+        assert_eq!(Config::Position(false).into_raw(), (ZBarConfig::ZBAR_CFG_POSITION, 0));
+    }
+
+    #[test]
+    fn test_into_raw_numeric() {
+        assert_eq!(Config::MinLen(4).into_raw(), (ZBarConfig::ZBAR_CFG_MIN_LEN, 4));
+        assert_eq!(Config::MaxLen(20).into_raw(), (ZBarConfig::ZBAR_CFG_MAX_LEN, 20));
+        assert_eq!(Config::XDensity(2).into_raw(), (ZBarConfig::ZBAR_CFG_X_DENSITY, 2));
+        assert_eq!(Config::YDensity(3).into_raw(), (ZBarConfig::ZBAR_CFG_Y_DENSITY, 3));
+    }
+}
@@ -4,6 +4,7 @@ use {
     ZBarResult,
     ZBarSymbolType
 };
+use std::ffi::CStr;
 
 pub struct Decoder {
     pub(crate) decoder: *mut ffi::zbar_decoder_s,
@@ -23,6 +24,59 @@ impl Decoder {
             e => Err(e.into())
         }
     }
+
+    /// Feeds a complete scan line of bar/space widths through the decoder.
+    ///
+    /// The first width has to be the leading quiet zone (a space), followed by alternating bars
+    /// and spaces. Returns the type and data of the first symbol that has been decoded
+    /// completely. QR codes are never returned as the decoder only locates their finder
+    /// patterns.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::decoder::Decoder;
+    ///
+    /// let decoder = Decoder::new();
+    /// assert!(decoder.decode_widths(&[10, 1, 1, 1, 10]).is_none());
+    /// ```
+    pub fn decode_widths(&self, widths: &[u32]) -> Option<(ZBarSymbolType, String)> {
+        unsafe { ffi::zbar_decoder_reset(self.decoder) };
+        widths
+            .iter()
+            .map(|width| unsafe { ffi::zbar_decode_width(self.decoder, *width) })
+            .find(|symbol_type| match *symbol_type {
+                ZBarSymbolType::ZBAR_NONE
+                | ZBarSymbolType::ZBAR_PARTIAL
+                | ZBarSymbolType::ZBAR_QRCODE => false,
+                _                              => true,
+            })
+            .map(|symbol_type| (
+                symbol_type,
+                unsafe { CStr::from_ptr(ffi::zbar_decoder_get_data(self.decoder)) }
+                    .to_string_lossy()
+                    .into_owned()
+            ))
+    }
+    /// Returns the direction in which the last symbol has been decoded.
+    ///
+    /// `Some(1)` means the symbol has been read forward, `Some(-1)` means it has been read in
+    /// reverse, e.g. because the capture is mirrored. Returns `None` if the direction is not
+    /// known.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::decoder::Decoder;
+    ///
+    /// assert!(Decoder::new().direction().is_none());
+    /// ```
+    pub fn direction(&self) -> Option<i8> {
+        match unsafe { ffi::zbar_decoder_get_direction(self.decoder) } {
+            0 => None,
+            d => Some(d.signum() as i8),
+        }
+    }
 }
 
 impl Default for Decoder {
@@ -32,3 +86,57 @@ impl Default for Decoder {
 impl Drop for Decoder {
     fn drop(&mut self) { unsafe { ffi::zbar_decoder_destroy(self.decoder) } }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const NARROW: u32 = 2;
+    const WIDE: u32 = 6;
+    const QUIET: u32 = 40;
+
+    // CODE-39 patterns: bar, space, bar, ... with 'w' being a wide and 'n' a narrow element
+    const START_STOP: &str = "nwnnwnwnn";
+    const A: &str = "wnnnnwnnw";
+
+    #[test]
+    fn test_decode_widths() {
+        assert_eq!(
+            Decoder::new().decode_widths(&code39_widths(&[START_STOP, A, START_STOP])),
+            Some((ZBarSymbolType::ZBAR_CODE39, "A".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_decode_widths_incomplete() {
+        assert!(Decoder::new().decode_widths(&code39_widths(&[START_STOP, A])).is_none());
+    }
+
+    #[test]
+    fn test_direction() {
+        let decoder = Decoder::new();
+        let mut widths = code39_widths(&[START_STOP, A, START_STOP]);
+        assert!(decoder.decode_widths(&widths).is_some());
+        assert_eq!(decoder.direction(), Some(1));
+
+        widths.reverse();
+        assert_eq!(
+            decoder.decode_widths(&widths),
+            Some((ZBarSymbolType::ZBAR_CODE39, "A".to_owned()))
+        );
+        assert_eq!(decoder.direction(), Some(-1));
+    }
+
+    fn code39_widths(patterns: &[&str]) -> Vec<u32> {
+        let mut widths = vec![QUIET];
+        patterns.iter().enumerate().for_each(|(i, pattern)| {
+            if i > 0 {
+                // inter-character gap
+                widths.push(NARROW);
+            }
+            widths.extend(pattern.chars().map(|c| if c == 'w' { WIDE } else { NARROW }));
+        });
+        widths.push(QUIET);
+        widths
+    }
+}
@@ -5,12 +5,20 @@ use std::{
 
 pub const Y800: Format = Format(0x5945_5247);
 pub const Y8: Format = Format(0x2020_3859);
+pub const YUYV: Format = Format(0x5659_5559);
+pub const NV12: Format = Format(0x3231_564E);
+/// The actual `Y800` FOURCC. The `Y800` constant above holds `GREY`, which ZBar treats as the
+/// same format.
+pub(crate) const Y800_FOURCC: Format = Format(0x3030_3859);
 
 /// A FOURCC code (https://www.fourcc.org/fourcc.php)
 ///
 /// The type `Format` holds the FOURCC label (e.g. Y800) and the corresponding FOURCC value.
 /// It can be constructed from an label or a FOURCC value.
 ///
+/// `Format`s are ordered by their FOURCC value, so they can be used as keys of a `HashMap` or
+/// `BTreeMap`.
+///
 /// # Examples
 ///
 /// Basic usage:
@@ -30,7 +38,7 @@ pub const Y8: Format = Format(0x2020_3859);
 /// let format = Format::from_value(0x30303859);
 /// println!("{}", format.as_label());
 /// ```
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
 pub struct Format(u32);
 impl Format {
     /// Creates a `Format` from the given FOURCC value.
@@ -72,6 +80,48 @@ impl Format {
     /// Returns the FOURCC value for this `Format`
     pub fn value(&self) -> u32 { self.into() }
     pub fn as_label(&self) -> String { self.to_string() }
+
+    /// Returns `true` for the 8-bit grayscale formats ZBar's image scanner expects (`GREY`,
+    /// `Y800` and `Y8`).
+    pub(crate) fn is_grayscale(&self) -> bool {
+        *self == Y800 || *self == Y8 || *self == Y800_FOURCC
+    }
+    /// Returns `true` if the size of a frame of this format is known exactly.
+    ///
+    /// Only such images can be handed to ZBar's conversion safely, as it reads as many bytes as
+    /// the format requires, regardless of the length of the buffer.
+    pub(crate) fn has_known_size(&self) -> bool { self.exact_frame_len(1, 1).is_some() }
+    /// Returns the number of bytes of a frame with the given dimensions or `None` on overflow.
+    ///
+    /// Formats of unknown size (see `has_known_size`) are assumed to take 1 byte per pixel.
+    pub(crate) fn frame_len(&self, width: u32, height: u32) -> Option<u32> {
+        self.exact_frame_len(width, height)
+            .unwrap_or_else(|| width.checked_mul(height))
+    }
+    /// Returns the number of bytes of a frame with the given dimensions, `Some(None)` on
+    /// overflow or `None` if the size of the format isn't known.
+    ///
+    /// Packed YUV 4:2:2 formats take 2 bytes per pixel and planar YUV 4:2:0 formats add two
+    /// quarter size chroma planes. Packed RGB formats take 2 to 4 bytes per pixel.
+    fn exact_frame_len(&self, width: u32, height: u32) -> Option<Option<u32>> {
+        let pixels = width.checked_mul(height);
+        if self.is_grayscale() {
+            return Some(pixels);
+        }
+        match &self.as_label()[..] {
+            "YUYV" | "YUY2" | "UYVY" | "YVYU" => Some(pixels.and_then(|len| len.checked_mul(2))),
+            "NV12" | "NV21" | "YU12" | "I420" | "YV12" => Some(
+                pixels.and_then(|len| {
+                    let chroma = (width / 2 + width % 2).checked_mul(height / 2 + height % 2)?;
+                    len.checked_add(chroma.checked_mul(2)?)
+                })
+            ),
+            "RGBP" | "RGBO" | "RGBR" | "RGBQ" => Some(pixels.and_then(|len| len.checked_mul(2))),
+            "RGB3" | "BGR3" => Some(pixels.and_then(|len| len.checked_mul(3))),
+            "RGB4" | "BGR4" => Some(pixels.and_then(|len| len.checked_mul(4))),
+            _ => None,
+        }
+    }
 }
 
 impl From<u32> for Format {
@@ -126,6 +176,71 @@ mod test {
         assert_eq!(Format::from_value(0x564E5559).value(), 0x564E5559);
     }
 
+    #[test]
+    fn test_hash_map_key() {
+        use std::collections::HashMap;
+
+        let mut map = HashMap::new();
+        map.insert(Format::from_label("Y800"), "Y800");
+        map.insert(Format::from_label("Y8"), "Y8");
+        map.insert(Format::from_label("YUYV"), "YUYV");
+
+        assert_eq!(map[&Format::from_label("Y800")], "Y800");
+        assert_eq!(map[&Format::from_label("Y8")], "Y8");
+        assert_eq!(map[&Format::from_value(0x5659_5559)], "YUYV");
+    }
+
+    #[test]
+    fn test_btree_set() {
+        use std::collections::BTreeSet;
+
+        let set = vec![Format::from_value(3), Format::from_value(1), Format::from_value(2)]
+            .into_iter()
+            .collect::<BTreeSet<_>>();
+
+        assert!(set.contains(&Format::from_value(2)));
+        assert_eq!(
+            set.into_iter().collect::<Vec<_>>(),
+            vec![Format::from_value(1), Format::from_value(2), Format::from_value(3)]
+        );
+    }
+
+    #[test]
+    fn test_frame_len() {
+        assert_eq!(Y800.frame_len(4, 2), Some(8));
+        assert_eq!(YUYV.frame_len(4, 2), Some(16));
+        assert_eq!(Format::from_label("YUYV"), YUYV);
+        assert_eq!(NV12.frame_len(4, 2), Some(12));
+        assert_eq!(NV12.frame_len(3, 3), Some(17));
+        assert_eq!(Format::from_label("NV12"), NV12);
+        assert_eq!(Y800.frame_len(u32::max_value(), 2), None);
+        assert_eq!(Format::from_label("RGB3").frame_len(4, 2), Some(24));
+        assert_eq!(Format::from_label("BGR4").frame_len(4, 2), Some(32));
+        assert_eq!(Format::from_label("RGBP").frame_len(4, 2), Some(16));
+        assert_eq!(Format::from_label("ABCD").frame_len(4, 2), Some(8));
+    }
+
+    #[test]
+    fn test_has_known_size() {
+        assert!(Y800.has_known_size());
+        assert!(Y800_FOURCC.has_known_size());
+        assert!(YUYV.has_known_size());
+        assert!(NV12.has_known_size());
+        assert!(Format::from_label("RGB3").has_known_size());
+        assert!(!Format::from_label("ABCD").has_known_size());
+        assert!(!Format::from_label("JPEG").has_known_size());
+    }
+
+    #[test]
+    fn test_is_grayscale() {
+        assert!(Y800.is_grayscale());
+        assert!(Y8.is_grayscale());
+        assert!(Format::from_label("Y800").is_grayscale());
+        assert_eq!(Format::from_label("Y800"), Y800_FOURCC);
+        assert!(!YUYV.is_grayscale());
+        assert!(!NV12.is_grayscale());
+    }
+
     #[test]
     fn test_eq() {
         assert_eq!(Format::from_label("YUNV"), Format::from_label("YUNV"));
@@ -1,5 +1,5 @@
 use {
-    as_char_ptr,
+    as_cstring,
     ffi,
     format::{
         Format,
@@ -7,34 +7,76 @@ use {
     },
     symbol::ZBarSymbol,
     symbol_set::ZBarSymbolSet,
+    ZBarError,
+    ZBarErrorType,
     ZBarResult,
 };
 #[cfg(feature = "from_image")]
 use image_crate::{
     self,
+    AnimationDecoder,
+    codecs::gif::GifDecoder,
     DynamicImage,
-    GenericImage,
-    imageops,
+    GenericImageView,
+    ImageError,
+    ImageFormat,
     ImageResult,
-    Pixel
+    Pixel,
+    Rgb,
+    RgbaImage,
+    RgbImage
+};
+#[cfg(feature = "from_image")]
+use std::{
+    fs::File,
+    io::{
+        BufReader,
+        Read
+    }
+};
+#[cfg(feature = "nokhwa")]
+use nokhwa::{
+    NokhwaError,
+    pixel_format::LumaFormat
 };
 use std::{
+    borrow::Cow,
+    cell::Cell,
     error::Error,
     rc::Rc,
     fmt,
+    mem,
     os::raw::c_void,
     path::Path,
     ptr,
-    slice::from_raw_parts
+    slice::from_raw_parts,
+    sync::Arc
 };
 
 pub type Result<T> = ::std::result::Result<ZBarImage<T>, ZBarImageError>;
 
+const UNCONVERTIBLE: &str = "image format can't be converted to Y800";
+
 unsafe extern fn image_destroyed_handler(_: *mut ffi::zbar_image_s) { trace!("free image"); }
 
+/// Runs the cleanup closure stored as user data by `ZBarImage::new_with_cleanup`.
+unsafe extern fn image_cleanup_handler(image: *mut ffi::zbar_image_s) {
+    trace!("free image with cleanup");
+    let cleanup = ffi::zbar_image_get_userdata(image) as *mut Box<dyn FnOnce()>;
+    if !cleanup.is_null() {
+        ffi::zbar_image_set_userdata(image, ptr::null_mut());
+        (Box::from_raw(cleanup))();
+    }
+}
+
 #[derive(Debug)]
 pub enum ZBarImageError {
     Len(u32, u32, usize),
+    NoData,
+    ZeroDimension(u32, u32),
+    DimensionOverflow(u32, u32),
+    /// The operation is only supported for 8-bit grayscale formats like `Y800` or `Y8`.
+    NotGrayscale(Format),
 }
 impl Error for ZBarImageError {}
 impl fmt::Display for ZBarImageError {
@@ -45,24 +87,133 @@ impl fmt::Display for ZBarImageError {
                 "width and height don't match actual data length\
                  => width: {}; height: {}; actual data length: {}",
                 w, h, l
-            )
+            ),
+            ZBarImageError::NoData => write!(f, "no image data has been provided"),
+            ZBarImageError::ZeroDimension(w, h) => write!(
+                f, "width and height must not be zero => width: {}; height: {}", w, h
+            ),
+            ZBarImageError::DimensionOverflow(w, h) => write!(
+                f, "width * height overflows 32 bits => width: {}; height: {}", w, h
+            ),
+            ZBarImageError::NotGrayscale(format) => write!(
+                f, "format is not 8-bit grayscale => format: {}", format
+            ),
         }
     }
 }
 
+/// Rotates and flips the image as described by the EXIF `orientation` (1 to 8).
+#[cfg(feature = "from_image")]
+fn oriented(image: DynamicImage, orientation: u32) -> DynamicImage {
+    match orientation {
+        2 => image.fliph(),
+        3 => image.rotate180(),
+        4 => image.flipv(),
+        5 => image.rotate90().fliph(),
+        6 => image.rotate90(),
+        7 => image.rotate270().fliph(),
+        8 => image.rotate270(),
+        _ => image,
+    }
+}
+
+/// Checks that the dimensions are valid and match the length of the image buffer.
+///
+/// ZBar computes buffer sizes with 32-bit arithmetic, so the frame length has to fit in a `u32`
+/// regardless of the target's pointer width. See `Format::frame_len` for the expected length of
+/// each format. YUV formats need their full frame, e.g. 2 bytes per pixel for `YUYV`, because
+/// ZBar reads that much when converting them. Buffers holding only the luminance plane have to
+/// be passed as `Y800` instead.
+fn check_dimensions(
+    width: u32,
+    height: u32,
+    format: Format,
+    len: usize
+) -> ::std::result::Result<(), ZBarImageError>
+{
+    if width == 0 || height == 0 {
+        return Err(ZBarImageError::ZeroDimension(width, height));
+    }
+    match format.frame_len(width, height) {
+        Some(size) if size as usize == len => Ok(()),
+        Some(_) => Err(ZBarImageError::Len(width, height, len)),
+        None => Err(ZBarImageError::DimensionOverflow(width, height)),
+    }
+}
+
+fn check_stride(
+    width: u32,
+    height: u32,
+    stride: u32,
+    len: usize
+) -> ::std::result::Result<(), ZBarImageError>
+{
+    if width == 0 || height == 0 {
+        return Err(ZBarImageError::ZeroDimension(width, height));
+    }
+    // the last row doesn't need to be padded
+    let required = (stride as usize)
+        .checked_mul(height as usize - 1)
+        .and_then(|len| len.checked_add(width as usize))
+        .ok_or(ZBarImageError::DimensionOverflow(width, height))?;
+    if stride < width || len < required {
+        Err(ZBarImageError::Len(width, height, len))
+    } else {
+        Ok(())
+    }
+}
+
+unsafe fn set_data(image: *mut ffi::zbar_image_s, data: &[u8]) {
+    ffi::zbar_image_set_data(
+        image,
+        data.as_ptr() as *mut c_void,
+        (data.len() as u32).into(),
+        Some(image_destroyed_handler)
+    );
+}
+
 pub(crate) fn set_ref(image: *mut ffi::zbar_image_s, refs: i32) {
     if !image.is_null() {
         unsafe { ffi::zbar_image_ref(image, refs) }
     }
 }
 
+/// An image to be scanned for symbols.
+///
+/// The pixel data is owned by the `ZBarImage` and shared between all of its clones through an
+/// `Rc`. ZBar itself only keeps a pointer to that data and never frees it: the cleanup handler
+/// passed to ZBar just logs. Every clone holds one ZBar reference to the underlying image and
+/// one strong count of the data, so the data lives exactly as long as the last clone.
+///
+/// `ZBarSymbol`s and `ZBarSymbolSet`s also hold ZBar references to the image, but not to the
+/// data. This is fine because they only access data ZBar copied while decoding.
+///
+/// ZBar expects the rows of pixels to be contiguous. Padded rows are tracked on the Rust side,
+/// see `set_stride`.
 pub struct ZBarImage<T> {
     image: *mut ffi::zbar_image_s,
     data: Rc<T>,
+    stride: Cell<Option<u32>>,
 }
 impl<T> ZBarImage<T> {
     pub(crate) fn image(&self) -> *mut ffi::zbar_image_s { self.image }
     fn set_ref(&self, refs: i32) { set_ref(self.image, refs) }
+    /// Returns the underlying ZBar image for interop with other ZBar-based C code.
+    ///
+    /// The pointer is owned by this `ZBarImage` and only valid as long as it is alive.
+    /// Callers must not destroy it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::ffi;
+    /// use zbars::prelude::*;
+    ///
+    /// let image = ZBarImage::new(1, 1, Y800, vec![0]).unwrap();
+    /// let raw: *mut ffi::zbar_image_s = image.as_raw();
+    /// assert!(!raw.is_null());
+    /// ```
+    pub fn as_raw(&self) -> *mut ffi::zbar_image_s { self.image }
     /// Returns the `Format` of the pixels.
     pub fn format(&self) -> Format {
         unsafe { (ffi::zbar_image_get_format(self.image) as u32).into() }
@@ -72,6 +223,90 @@ impl<T> ZBarImage<T> {
     pub fn width(&self) -> u32 { unsafe { ffi::zbar_image_get_width(self.image) } }
     /// Returns the height of the image in pixels
     pub fn height(&self) -> u32 { unsafe { ffi::zbar_image_get_height(self.image) } }
+    /// Returns the number of bytes between the starts of two consecutive rows.
+    ///
+    /// This is the width unless the rows are padded, see `new_strided` and `set_stride`.
+    pub fn stride(&self) -> u32 { self.stride.get().unwrap_or_else(|| self.width()) }
+    /// Sets the number of bytes between the starts of two consecutive rows, e.g. for DMA
+    /// buffers whose rows are padded for alignment.
+    ///
+    /// ZBar has no notion of a stride, so it is stored with this `ZBarImage` rather than with
+    /// the underlying ZBar image. Clones copy the stride when they are created, but don't
+    /// follow later changes. `pixel`, `rows`, `luma_histogram`, `convert` and the
+    /// transformations skip the padding, and `ZBarImageScanner::scan_image` and
+    /// `ZBarProcessor::process_image` scan a copy of the image with the padding stripped.
+    /// `data` still returns the whole buffer including the padding.
+    ///
+    /// Returns an error if the format isn't 8-bit grayscale like `Y800` or `Y8`, if `stride` is
+    /// smaller than the width or if the buffer is too short for `height` rows of `stride`
+    /// bytes. The last row doesn't need to be padded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    ///
+    /// // 2x2 pixels in rows of 4 bytes, the last 2 of which are padding
+    /// let image = ZBarImage::new_strided(2, 2, 4, vec![1, 2, 0, 0, 3, 4, 0, 0]).unwrap();
+    /// assert_eq!(image.pixel(0, 1), Some(3));
+    /// // the buffer is too short for rows of 8 bytes
+    /// assert!(image.set_stride(8).is_err());
+    /// ```
+    pub fn set_stride(&self, stride: u32) -> ::std::result::Result<(), ZBarImageError> {
+        if !self.format().is_grayscale() {
+            return Err(ZBarImageError::NotGrayscale(self.format()));
+        }
+        check_stride(self.width(), self.height(), stride, self.data_len())?;
+        self.stride.set(Some(stride));
+        Ok(())
+    }
+    /// Returns an iterator over the rows of pixels without their padding.
+    ///
+    /// Only meaningful for 8-bit grayscale formats such as `Y800` or `Y8`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    ///
+    /// let image = ZBarImage::new_strided(2, 2, 3, vec![1, 2, 0, 3, 4]).unwrap();
+    /// assert_eq!(image.rows().collect::<Vec<_>>(), vec![&[1, 2][..], &[3, 4][..]]);
+    /// ```
+    pub fn rows<'a>(&'a self) -> impl Iterator<Item = &'a [u8]> + 'a {
+        let width = self.width() as usize;
+        self.data()
+            .chunks(self.stride() as usize)
+            .take(self.height() as usize)
+            .map(move |row| &row[..width])
+    }
+    /// Returns the pixels without the padding of strided images.
+    fn packed_data(&self) -> Cow<[u8]> {
+        match self.stride.get() {
+            Some(_) => Cow::Owned(self.rows().flat_map(|row| row.iter().cloned()).collect()),
+            None    => Cow::Borrowed(self.data()),
+        }
+    }
+    /// Returns a copy of this image with the padding stripped, or `None` if it has no stride.
+    ///
+    /// The sequence number, userdata and crop are copied, so the copy is scanned like this
+    /// image would be.
+    pub(crate) fn packed(&self) -> Option<ZBarImage<Vec<u8>>> {
+        self.stride.get().map(|_| {
+            // a stride is only ever set for grayscale images with checked dimensions
+            let packed = ZBarImage::new(
+                self.width(), self.height(), self.format(), self.packed_data().into_owned()
+            ).unwrap();
+            packed.set_sequence(self.sequence());
+            // the copy never interprets the userdata, so sharing the pointer is fine
+            unsafe { ffi::zbar_image_set_userdata(packed.image, self.userdata()) };
+            #[cfg(feature = "zbar_fork")]
+            {
+                let (x, y, width, height) = self.crop();
+                packed.set_crop(x, y, width, height);
+            }
+            packed
+        })
+    }
 
     /// Retrieves the image buffer.
     ///
@@ -87,10 +322,253 @@ impl<T> ZBarImage<T> {
         unsafe {
             from_raw_parts(
                 ffi::zbar_image_get_data(self.image) as *const u8,
-                ffi::zbar_image_get_data_length(self.image) as usize
+                self.data_len()
             )
         }
     }
+    /// Returns a FNV-1a hash of the image buffer.
+    ///
+    /// Not cryptographically secure, but cheap enough to detect unchanged video frames before
+    /// scanning them again.
+    pub fn content_hash(&self) -> u64 {
+        self.data()
+            .iter()
+            .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+                (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+            })
+    }
+    /// Returns a mutable reference to the image buffer.
+    ///
+    /// Returns `None` if the buffer is shared with a clone of this image. As ZBar refers to
+    /// the buffer directly, changes are visible to subsequent scans.
+    pub fn data_mut(&mut self) -> Option<&mut [u8]> where T: AsMut<[u8]> {
+        Rc::get_mut(&mut self.data).map(AsMut::as_mut)
+    }
+    /// Returns the luminance of the pixel at (`x`, `y`) or `None` if it is out of range.
+    ///
+    /// Only meaningful for 8-bit grayscale formats such as `Y800` or `Y8`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    ///
+    /// let image = ZBarImage::new(2, 1, Y800, vec![0, 255]).unwrap();
+    /// assert_eq!(image.pixel(1, 0), Some(255));
+    /// assert_eq!(image.pixel(2, 0), None);
+    /// ```
+    pub fn pixel(&self, x: u32, y: u32) -> Option<u8> {
+        if x < self.width() && y < self.height() {
+            self.data().get(y as usize * self.stride() as usize + x as usize).cloned()
+        } else {
+            None
+        }
+    }
+    /// Counts the occurrences of each luminance value.
+    ///
+    /// Useful to detect under- or overexposed frames that won't decode. Only meaningful for
+    /// 8-bit grayscale formats such as `Y800` or `Y8`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    ///
+    /// let image = ZBarImage::new(3, 1, Y800, vec![0, 0, 255]).unwrap();
+    /// let histogram = image.luma_histogram();
+    /// assert_eq!((histogram[0], histogram[255]), (2, 1));
+    /// ```
+    pub fn luma_histogram(&self) -> [u32; 256] {
+        let mut histogram = [0; 256];
+        self.packed_data().iter().for_each(|luma| histogram[*luma as usize] += 1);
+        histogram
+    }
+    /// Returns whether the luminance range (maximum minus minimum) exceeds `min_spread`.
+    ///
+    /// This cheaply rejects blank or flat frames before scanning them. Images without data
+    /// never have sufficient contrast.
+    pub fn has_sufficient_contrast(&self, min_spread: u8) -> bool {
+        let histogram = self.luma_histogram();
+        match (
+            histogram.iter().position(|count| *count > 0),
+            histogram.iter().rposition(|count| *count > 0)
+        ) {
+            (Some(min), Some(max)) => max - min > min_spread as usize,
+            _                      => false,
+        }
+    }
+    /// Returns the 8-bit grayscale pixels of this image together with their format.
+    ///
+    /// Other formats are converted to `Y800` first. Returns `None` if ZBar can't convert them.
+    fn grayscale_data(&self) -> Option<(Format, Cow<[u8]>)> {
+        if self.format().is_grayscale() {
+            Some((self.format(), self.packed_data()))
+        } else {
+            self.convert(Y800).map(|image| (Y800, Cow::Owned(image.data().to_vec())))
+        }
+    }
+    /// Returns a copy of this image rotated 90° clockwise.
+    ///
+    /// The width and height of the new image are swapped. This is useful for 1D barcodes that
+    /// were captured sideways and thus can't be decoded along the scanned axis. Images that are
+    /// not 8-bit grayscale like `Y800` or `Y8` are converted to `Y800`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if ZBar can't convert the image format to `Y800`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    ///
+    /// let image = ZBarImage::new(2, 1, Y800, vec![1, 2]).unwrap();
+    /// let rotated = image.rotated_90();
+    /// assert_eq!((rotated.width(), rotated.height()), (1, 2));
+    /// assert_eq!(rotated.data(), &[1, 2]);
+    /// ```
+    pub fn rotated_90(&self) -> ZBarImage<Vec<u8>> {
+        let (width, height) = (self.width() as usize, self.height() as usize);
+        let (format, data) = self.grayscale_data().expect(UNCONVERTIBLE);
+        let rotated = (0..width)
+            .flat_map(|x| (0..height).rev().map(move |y| (x, y)))
+            .map(|(x, y)| data[y * width + x])
+            .collect();
+        ZBarImage::new(height as u32, width as u32, format, rotated).unwrap()
+    }
+    /// Returns a copy of this image mirrored horizontally, i.e. with every row reversed.
+    ///
+    /// This undoes the mirroring of front-facing cameras. Images that are not 8-bit grayscale
+    /// like `Y800` or `Y8` are converted to `Y800`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if ZBar can't convert the image format to `Y800`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    ///
+    /// let image = ZBarImage::new(2, 1, Y800, vec![1, 2]).unwrap();
+    /// assert_eq!(image.flipped_h().data(), &[2, 1]);
+    /// ```
+    pub fn flipped_h(&self) -> ZBarImage<Vec<u8>> {
+        let (width, height) = (self.width() as usize, self.height() as usize);
+        let (format, data) = self.grayscale_data().expect(UNCONVERTIBLE);
+        let flipped = data[..width * height]
+            .chunks(width)
+            .flat_map(|row| row.iter().rev().cloned())
+            .collect();
+        ZBarImage::new(width as u32, height as u32, format, flipped).unwrap()
+    }
+    /// Returns a copy of this image mirrored vertically, i.e. with the rows in reverse order.
+    ///
+    /// Images that are not 8-bit grayscale like `Y800` or `Y8` are converted to `Y800`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if ZBar can't convert the image format to `Y800`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    ///
+    /// let image = ZBarImage::new(1, 2, Y800, vec![1, 2]).unwrap();
+    /// assert_eq!(image.flipped_v().data(), &[2, 1]);
+    /// ```
+    pub fn flipped_v(&self) -> ZBarImage<Vec<u8>> {
+        let (width, height) = (self.width() as usize, self.height() as usize);
+        let (format, data) = self.grayscale_data().expect(UNCONVERTIBLE);
+        let flipped = data[..width * height]
+            .chunks(width)
+            .rev()
+            .flat_map(|row| row.iter().cloned())
+            .collect();
+        ZBarImage::new(width as u32, height as u32, format, flipped).unwrap()
+    }
+    /// Returns a copy of this image centered in a larger one filled with `fill`.
+    ///
+    /// This adds a quiet zone around barcodes that are cropped too tightly, e.g. with a `fill`
+    /// of `255` for white. Images that are not 8-bit grayscale like `Y800` or `Y8` are
+    /// converted to `Y800`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the target size is smaller than the image or if ZBar can't convert the image
+    /// format to `Y800`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    ///
+    /// let image = ZBarImage::new(1, 1, Y800, vec![0]).unwrap();
+    /// assert_eq!(image.padded(3, 1, 255).data(), &[255, 0, 255]);
+    /// ```
+    pub fn padded(&self, target_width: u32, target_height: u32, fill: u8) -> ZBarImage<Vec<u8>> {
+        let (width, height) = (self.width() as usize, self.height() as usize);
+        let (target_width, target_height) = (target_width as usize, target_height as usize);
+        assert!(
+            target_width >= width && target_height >= height,
+            "target size {}x{} is smaller than image size {}x{}",
+            target_width, target_height, width, height
+        );
+        let (format, data) = self.grayscale_data().expect(UNCONVERTIBLE);
+        let (left, top) = ((target_width - width) / 2, (target_height - height) / 2);
+        let mut padded = vec![fill; target_width * target_height];
+        data[..width * height]
+            .chunks(width)
+            .enumerate()
+            .for_each(|(y, row)| {
+                let start = (top + y) * target_width + left;
+                padded[start..start + width].copy_from_slice(row);
+            });
+        ZBarImage::new(target_width as u32, target_height as u32, format, padded).unwrap()
+    }
+    /// Returns the length of the image buffer in bytes without accessing the buffer itself.
+    pub fn data_len(&self) -> usize {
+        unsafe { ffi::zbar_image_get_data_length(self.image) as usize }
+    }
+    /// Converts this image to the given `format` using ZBar's built-in conversion.
+    ///
+    /// The converted image owns a new buffer allocated by ZBar. Returns `None` if ZBar doesn't
+    /// support the conversion or the exact size of the source format isn't known. ZBar reads as
+    /// many bytes as the source format requires, so images whose length could only be checked
+    /// against 1 byte per pixel (e.g. `Format::from_label("ABCD")`) are never converted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    ///
+    /// // 2x1 pixels: Y0 U Y1 V
+    /// let yuyv = ZBarImage::new(2, 1, YUYV, vec![16, 128, 235, 128]).unwrap();
+    /// let grey = yuyv.convert(Y800).unwrap();
+    /// assert_eq!(grey.data(), &[16, 235]);
+    /// ```
+    pub fn convert(&self, format: Format) -> Option<ZBarImage<()>> {
+        if !self.format().has_known_size() {
+            return None;
+        }
+        if let Some(packed) = self.packed() {
+            return packed.convert(format);
+        }
+        let image = unsafe { ffi::zbar_image_convert(self.image, format.value().into()) };
+        if image.is_null() {
+            None
+        } else {
+            let image = ZBarImage { image, data: Rc::new(()), stride: Cell::new(None) };
+            // keep the region to scan, the converted image has the same dimensions
+            #[cfg(feature = "zbar_fork")]
+            {
+                let (x, y, width, height) = self.crop();
+                image.set_crop(x, y, width, height);
+            }
+            Some(image)
+        }
+    }
     /// Returns an `Option` containing the `SymbolSet` or `None` if the image hasn't been scanned.
     ///
     /// # Examples
@@ -125,9 +603,23 @@ impl<T> ZBarImage<T> {
     pub fn first_symbol(&self) -> Option<ZBarSymbol> {
         ZBarSymbol::from_raw(unsafe { ffi::zbar_image_first_symbol(self.image) }, self.image)
     }
+    /// Returns the application data attached with `ImageBuilder::with_userdata`, or a null
+    /// pointer if there is none.
+    ///
+    /// ZBar never dereferences it. Images created by `new_with_cleanup` store their cleanup
+    /// closure there, which is why the userdata can't be changed after creation.
+    pub fn userdata(&self) -> *mut c_void { unsafe { ffi::zbar_image_get_userdata(self.image) } }
     pub fn set_sequence(&self, sequence_num: u32) {
         unsafe { ffi::zbar_image_set_sequence(self.image, sequence_num) }
     }
+    /// Increments the sequence number and returns the new value.
+    ///
+    /// Handy for frame loops that reuse one image. Wraps around on overflow.
+    pub fn next_sequence(&self) -> u32 {
+        let sequence = self.sequence().wrapping_add(1);
+        self.set_sequence(sequence);
+        sequence
+    }
 
     /// Just a crop with origin
     pub fn set_size(&self, width: u32, height: u32) {
@@ -136,9 +628,12 @@ impl<T> ZBarImage<T> {
 
     /// Writes image on `ZBar format` to the given path.
     pub fn write(&self, path: impl AsRef<Path>) -> ZBarResult<()> {
-        match unsafe {
-            ffi::zbar_image_write(self.image, as_char_ptr(path.as_ref().to_str().unwrap()))
-        } {
+        let path = path
+            .as_ref()
+            .to_str()
+            .ok_or(ZBarErrorType::Complex(ZBarError::ZBAR_ERR_INVALID))
+            .and_then(as_cstring)?;
+        match unsafe { ffi::zbar_image_write(self.image, path.as_ptr()) } {
             0 => Ok(()),
             e => Err(e.into()),
         }
@@ -184,6 +679,31 @@ impl<T> ZBarImage<T> {
 }
 
 impl<T> ZBarImage<T> where T: AsRef<[u8]> {
+    /// Creates an `ImageBuilder` for an image of the given dimensions and `Format`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    ///
+    /// let image = ZBarImage::builder(2, 3, Y800)
+    ///     .with_data(vec![0; 2 * 3])
+    ///     .with_sequence(1)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(image.sequence(), 1);
+    /// ```
+    pub fn builder(width: u32, height: u32, format: Format) -> ImageBuilder<T> {
+        ImageBuilder::new(width, height, format)
+    }
+    /// Creates an image of the given dimensions and `Format` from `data`.
+    ///
+    /// Returns an error if the length of `data` doesn't match the format: packed YUV 4:2:2
+    /// formats like `YUYV` take 2 bytes per pixel, planar YUV 4:2:0 formats like `NV12` 1.5
+    /// bytes per pixel and all other formats 1 byte per pixel.
+    ///
+    /// The data has to outlive the image:
+    ///
     /// ```compile_fail
     /// use zbars::prelude::*;
     ///
@@ -193,25 +713,285 @@ impl<T> ZBarImage<T> where T: AsRef<[u8]> {
     /// };
     /// ```
     pub fn new(width: u32, height: u32, format: Format, data: T) -> Result<T> {
-        if width as usize * height as usize == data.as_ref().len() {
-            unsafe {
-                let image = ffi::zbar_image_create();
-                ffi::zbar_image_set_format(image, format.value().into());
-                ffi::zbar_image_set_size(image, width, height);
-                ffi::zbar_image_set_data(
-                    image,
-                    data.as_ref().as_ptr() as *mut c_void,
-                    (data.as_ref().len() as u32).into(),
-                    Some(image_destroyed_handler)
-                );
-                Ok(Self { image, data: data.into() })
+        check_dimensions(width, height, format, data.as_ref().len())?;
+        Ok(Self::create(width, height, format, data))
+    }
+    /// Creates a `Y800` image from 8-bit grayscale rows that are `stride` bytes apart without
+    /// copying them.
+    ///
+    /// Captured frames often pad every row for alignment, e.g. DMA buffers. The last row
+    /// doesn't need to be padded. See `set_stride` for how the padding is handled. Returns an
+    /// error if `stride` is smaller than `width` or `data` is too short.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    ///
+    /// // 2x2 pixels, rows padded to 4 bytes
+    /// let image = ZBarImage::new_strided(2, 2, 4, vec![1, 2, 0, 0, 3, 4, 0, 0]).unwrap();
+    /// assert_eq!(image.stride(), 4);
+    /// assert_eq!(image.pixel(0, 1), Some(3));
+    /// ```
+    pub fn new_strided(width: u32, height: u32, stride: u32, data: T) -> Result<T> {
+        check_stride(width, height, stride, data.as_ref().len())?;
+        let image = Self::create(width, height, Y800, data);
+        image.stride.set(Some(stride));
+        Ok(image)
+    }
+    fn create(width: u32, height: u32, format: Format, data: T) -> Self {
+        // The data has to be moved into its final place before handing its address to ZBar.
+        // Otherwise data stored inline (e.g. `[u8; N]`) would leave ZBar with a dangling
+        // pointer.
+        let data = Rc::new(data);
+        unsafe {
+            let image = ffi::zbar_image_create();
+            ffi::zbar_image_set_format(image, format.value().into());
+            ffi::zbar_image_set_size(image, width, height);
+            set_data(image, (*data).as_ref());
+            Self { image, data, stride: Cell::new(None) }
+        }
+    }
+    /// Converts this image into a `SharedZBarImage` that can be sent to other threads.
+    ///
+    /// The data is moved from the `Rc` into an `Arc` without copying it. Returns the image
+    /// unchanged if it is still shared with clones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    /// use zbars::prelude::*;
+    ///
+    /// let image = ZBarImage::new(2, 1, Y800, vec![1, 2]).unwrap().into_shared().unwrap();
+    /// thread::spawn(move || assert_eq!(image.data(), &[1, 2])).join().unwrap();
+    /// ```
+    pub fn into_shared(self) -> ::std::result::Result<SharedZBarImage<T>, Self> {
+        let (image, stride) = (self.image, self.stride.get());
+        let data = unsafe { ptr::read(&self.data) };
+        mem::forget(self);
+        match Rc::try_unwrap(data) {
+            Ok(data) => {
+                let data = Arc::new(data);
+                // data stored inline has been moved
+                unsafe { set_data(image, (*data).as_ref()) };
+                Ok(SharedZBarImage { image, data, stride })
+            }
+            Err(data) => Err(ZBarImage { image, data, stride: Cell::new(stride) }),
+        }
+    }
+    /// Returns the image data without copying it, detaching it from ZBar.
+    ///
+    /// Returns `None` if the data is still shared with clones of this image. `ZBarSymbol`s and
+    /// `ZBarSymbolSet`s of the image stay valid as they don't access the data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    ///
+    /// let image = ZBarImage::new(2, 1, Y800, vec![1, 2]).unwrap();
+    /// assert_eq!(image.into_inner(), Some(vec![1, 2]));
+    /// ```
+    pub fn into_inner(self) -> Option<T> {
+        let image = self.image;
+        let data = unsafe { ptr::read(&self.data) };
+        mem::forget(self);
+        match Rc::try_unwrap(data) {
+            Ok(data) => {
+                unsafe { ffi::zbar_image_set_data(image, ptr::null_mut(), 0_u32.into(), None) };
+                set_ref(image, -1);
+                Some(data)
+            }
+            Err(data) => {
+                drop(ZBarImage { image, data, stride: Cell::new(None) });
+                None
             }
-        } else {
-            Err(ZBarImageError::Len(width, height, data.as_ref().len()))
         }
     }
 }
 
+impl ZBarImage<()> {
+    /// Creates a `ZBarImage` from an externally owned buffer without copying it.
+    ///
+    /// This is meant for zero-copy scanning of buffers that are not owned by Rust, e.g.
+    /// memory-mapped V4L2 capture buffers. The image never frees the buffer.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to `len` readable bytes that stay valid and are not mutated for as long
+    /// as the returned image or any of its clones is alive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    ///
+    /// let buf = vec![0; 2 * 3];
+    /// let image = unsafe { ZBarImage::from_raw_ptr(2, 3, Y800, buf.as_ptr(), buf.len()) }
+    ///     .unwrap();
+    /// assert_eq!(image.data(), buf.as_slice());
+    /// ```
+    pub unsafe fn from_raw_ptr(
+        width: u32,
+        height: u32,
+        format: Format,
+        ptr: *const u8,
+        len: usize
+    ) -> Result<()>
+    {
+        check_dimensions(width, height, format, len)?;
+        let image = ffi::zbar_image_create();
+        ffi::zbar_image_set_format(image, format.value().into());
+        ffi::zbar_image_set_size(image, width, height);
+        ffi::zbar_image_set_data(
+            image,
+            ptr as *mut c_void,
+            (len as u32).into(),
+            Some(image_destroyed_handler)
+        );
+        Ok(Self { image, data: Rc::new(()), stride: Cell::new(None) })
+    }
+    /// Like `from_raw_ptr`, but runs `cleanup` once ZBar releases the buffer.
+    ///
+    /// This allows zero-copy scanning of buffers from foreign allocators that have to be freed
+    /// or unmapped explicitly. ZBar releases the buffer when the last clone of the image and all
+    /// `ZBarSymbol`s and `ZBarSymbolSet`s referring to it have been dropped. If the dimensions
+    /// are invalid an error is returned and `cleanup` is dropped without being called.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to `len` readable bytes that stay valid and are not mutated until
+    /// `cleanup` is called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    ///
+    /// let buf = vec![0; 2 * 3];
+    /// let (ptr, len) = (buf.as_ptr(), buf.len());
+    /// let image = unsafe {
+    ///     ZBarImage::new_with_cleanup(2, 3, Y800, ptr, len, move || drop(buf))
+    /// }.unwrap();
+    /// ```
+    pub unsafe fn new_with_cleanup<F>(
+        width: u32,
+        height: u32,
+        format: Format,
+        ptr: *const u8,
+        len: usize,
+        cleanup: F
+    ) -> Result<()>
+        where F: FnOnce() + 'static
+    {
+        check_dimensions(width, height, format, len)?;
+        let cleanup: Box<Box<dyn FnOnce()>> = Box::new(Box::new(cleanup));
+        let image = ffi::zbar_image_create();
+        ffi::zbar_image_set_format(image, format.value().into());
+        ffi::zbar_image_set_size(image, width, height);
+        ffi::zbar_image_set_userdata(image, Box::into_raw(cleanup) as *mut c_void);
+        ffi::zbar_image_set_data(
+            image,
+            ptr as *mut c_void,
+            (len as u32).into(),
+            Some(image_cleanup_handler)
+        );
+        Ok(Self { image, data: Rc::new(()), stride: Cell::new(None) })
+    }
+}
+
+impl ZBarImage<Vec<u8>> {
+    /// Creates a `Y800` image from 16-bit grayscale pixels by keeping their upper 8 bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    ///
+    /// let image = ZBarImage::from_luma16(2, 1, &[0x0000, 0xff00]).unwrap();
+    /// assert_eq!(image.data(), &[0x00, 0xff]);
+    /// ```
+    pub fn from_luma16(width: u32, height: u32, data: &[u16]) -> Result<Vec<u8>> {
+        Self::from_luma16_shifted(width, height, data, 8)
+    }
+
+    /// Creates a `Y800` image from 16-bit grayscale pixels by right-shifting every pixel by
+    /// `shift` bits.
+    ///
+    /// Values that still exceed 8 bits after shifting saturate to `255`. A smaller shift than
+    /// the default of `8` brightens dark images that only use the lower range.
+    pub fn from_luma16_shifted(
+        width: u32,
+        height: u32,
+        data: &[u16],
+        shift: u32
+    ) -> Result<Vec<u8>>
+    {
+        ZBarImage::new(
+            width,
+            height,
+            Y800,
+            data.iter()
+                .map(|pixel| pixel.checked_shr(shift).unwrap_or(0).min(255) as u8)
+                .collect()
+        )
+    }
+
+    /// Creates a `Y800` image from 8-bit grayscale rows that are `stride` bytes apart.
+    ///
+    /// Unlike `new_strided`, the padding is stripped by copying the rows into a new buffer, so
+    /// ZBar can scan the image directly. The last row doesn't need to be padded. Returns an
+    /// error if `stride` is smaller than `width` or `data` is too short.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    ///
+    /// // 2x2 pixels, rows padded to 4 bytes
+    /// let image = ZBarImage::from_strided(2, 2, 4, &[1, 2, 0, 0, 3, 4, 0, 0]).unwrap();
+    /// assert_eq!(image.data(), &[1, 2, 3, 4]);
+    /// ```
+    pub fn from_strided(width: u32, height: u32, stride: u32, data: &[u8]) -> Result<Vec<u8>> {
+        // safe to unwrap as strided images are always packed
+        Ok(ZBarImage::new_strided(width, height, stride, data)?.packed().unwrap())
+    }
+}
+
+#[cfg(feature = "nokhwa")]
+impl ZBarImage<Vec<u8>> {
+    /// Creates a `Y800` image from a frame captured with the `nokhwa` camera crate.
+    ///
+    /// The frame is decoded from its source format (e.g. MJPEG, YUYV or NV12) straight into
+    /// grayscale. Returns an error if decoding fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate nokhwa;
+    /// extern crate zbars;
+    ///
+    /// use nokhwa::{
+    ///     Buffer,
+    ///     utils::{FrameFormat, Resolution}
+    /// };
+    /// use zbars::image::ZBarImage;
+    ///
+    /// fn main() {
+    ///     let frame = Buffer::new(Resolution::new(2, 1), &[0, 255], FrameFormat::GRAY);
+    ///     let image = ZBarImage::from_nokhwa_frame(&frame).unwrap();
+    ///     assert_eq!(image.data(), &[0, 255]);
+    /// }
+    /// ```
+    pub fn from_nokhwa_frame(frame: &nokhwa::Buffer) -> ::std::result::Result<Self, NokhwaError> {
+        let luma = frame.decode_image::<LumaFormat>()?;
+        let (width, height) = luma.dimensions();
+        // Safe to unwrap here as nokhwa ensures the decoded buffer matches its dimensions
+        Ok(ZBarImage::new(width, height, Y800, luma.into_raw()).unwrap())
+    }
+}
+
 #[cfg(feature = "from_image")]
 impl ZBarImage<Vec<u8>> {
     /// Creates a `ZBarImage` from the given path.
@@ -234,6 +1014,83 @@ impl ZBarImage<Vec<u8>> {
         image_crate::open(&path).map(Self::from)
     }
 
+    /// Like `from_path`, but applies the EXIF orientation of the image first.
+    ///
+    /// Phone cameras usually store photos as captured and only tag them with the orientation
+    /// they should be displayed in. The image is rotated and flipped accordingly, so it is
+    /// scanned upright. Images without EXIF orientation are loaded unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::image::ZBarImage;
+    ///
+    /// let image = ZBarImage::from_path_respecting_orientation("test/code128_exif_rotate90.png")
+    ///     .unwrap();
+    /// assert!(image.width() > image.height());
+    /// ```
+    pub fn from_path_respecting_orientation(path: impl AsRef<Path>) -> ImageResult<Self> {
+        let image = image_crate::open(&path)?;
+        let orientation = File::open(&path)
+            .ok()
+            .and_then(|file| {
+                exif::Reader::new().read_from_container(&mut BufReader::new(file)).ok()
+            })
+            .and_then(|exif| {
+                exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY)
+                    .and_then(|field| field.value.get_uint(0))
+            })
+            .unwrap_or(1);
+        Ok(oriented(image, orientation).into())
+    }
+
+    /// Creates a `ZBarImage` for every frame of the (animated) GIF at the given path.
+    ///
+    /// Every frame is composited onto the full canvas like it would be displayed, so all images
+    /// have the dimensions of the GIF.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::image::ZBarImage;
+    ///
+    /// let frames = ZBarImage::from_gif_frames("test/frames.gif").unwrap();
+    /// assert_eq!(frames.len(), 3);
+    /// ```
+    pub fn from_gif_frames(path: impl AsRef<Path>) -> ImageResult<Vec<Self>> {
+        let file = File::open(path).map_err(ImageError::IoError)?;
+        GifDecoder::new(BufReader::new(file))?
+            .into_frames()
+            .map(|frame| frame.map(|frame| DynamicImage::ImageRgba8(frame.into_buffer()).into()))
+            .collect()
+    }
+
+    /// Creates a `ZBarImage` by decoding an image of the given `format` from `reader`.
+    ///
+    /// The stream is read to its end before decoding, so any `Read` such as an HTTP response
+    /// body can be used without touching the filesystem.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate zbars;
+    /// extern crate image;
+    ///
+    /// use image::ImageFormat;
+    /// use std::fs::File;
+    /// use zbars::image::ZBarImage;
+    ///
+    /// fn main() {
+    ///     let file = File::open("test/code128.gif").unwrap();
+    ///     let image = ZBarImage::from_reader(file, ImageFormat::Gif).unwrap();
+    /// }
+    /// ```
+    pub fn from_reader<R: Read>(mut reader: R, format: ImageFormat) -> ImageResult<Self> {
+        let mut buffer = vec![];
+        reader.read_to_end(&mut buffer).map_err(ImageError::IoError)?;
+        image_crate::load_from_memory_with_format(&buffer, format).map(Self::from)
+    }
+
     /// Creates a `ZBarImage` from a `DynamicImage`.
     ///
     /// The given image will owned so zero copy takes place if the image is already a
@@ -258,11 +1115,11 @@ impl ZBarImage<Vec<u8>> {
     /// ```
     pub fn from_dyn_image(image: DynamicImage) -> Self { image.into() }
 
-    /// Creates a `ZBarImage` from a `GenericImage`.
+    /// Creates a `ZBarImage` from a `GenericImageView`.
     ///
-    /// As the pixel representation is not known for a `GenericImage` it will always
+    /// As the pixel representation is not known for a `GenericImageView` it will always
     /// be grayscaled and thus a new image buffer will be allocated. If possible use
-    /// `ZBarImage::from_dyn_image` instead. Use this if you want to use `GenericImage`
+    /// `ZBarImage::from_dyn_image` instead. Use this if you want to use the image
     /// beyond this.
     ///
     /// # Examples
@@ -281,11 +1138,91 @@ impl ZBarImage<Vec<u8>> {
     ///     )
     /// );
     /// ```
-    pub fn from_generic_image<I>(image: &I) -> Self
-        where I: GenericImage + 'static,
-              Vec<u8>: From<Vec<<<I as GenericImage>::Pixel as Pixel>::Subpixel>>
+    pub fn from_generic_image<I>(image: &I) -> Self
+        where I: GenericImageView,
+              I::Pixel: Pixel<Subpixel = u8>
+    {
+        Self::create_image(
+            image.dimensions(),
+            image.pixels().map(|(_, _, pixel)| pixel.to_luma().0[0]).collect()
+        )
+    }
+
+    /// Creates a `ZBarImage` from an RGBA image by blending it onto a gray `background`.
+    ///
+    /// `From<DynamicImage>` simply drops the alpha channel, so transparent regions end up with
+    /// whatever color they happen to store. This is a problem for screenshots of barcodes on
+    /// transparent canvases. Here each pixel is weighted by its alpha, so fully transparent
+    /// regions become `background`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate zbars;
+    /// extern crate image;
+    ///
+    /// use zbars::image::ZBarImage;
+    /// use image::RgbaImage;
+    ///
+    /// let image = ZBarImage::from_rgba8_with_background(
+    ///     &RgbaImage::from_raw(1, 1, vec![0, 0, 0, 0]).unwrap(),
+    ///     255
+    /// );
+    /// assert_eq!(image.data(), &[255]);
+    /// ```
+    pub fn from_rgba8_with_background(image: &RgbaImage, background: u8) -> Self {
+        Self::create_image(
+            image.dimensions(),
+            image.pixels()
+                .map(|pixel| {
+                    let luma = u32::from(Rgb([pixel[0], pixel[1], pixel[2]]).to_luma()[0]);
+                    let alpha = u32::from(pixel[3]);
+                    ((luma * alpha + u32::from(background) * (255 - alpha)) / 255) as u8
+                })
+                .collect()
+        )
+    }
+
+    /// Creates a `ZBarImage` from an RGB image using custom weights for the luminance.
+    ///
+    /// Each pixel becomes `r * r_weight + g * g_weight + b * b_weight`, clamped to `0..=255`.
+    /// The standard luminance of colored bars on a colored background may hardly differ, while
+    /// weighting a single channel can still tell them apart.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate zbars;
+    /// extern crate image;
+    ///
+    /// use zbars::image::ZBarImage;
+    /// use image::RgbImage;
+    ///
+    /// // only take the green channel into account
+    /// let image = ZBarImage::from_rgb8_weighted(
+    ///     &RgbImage::from_raw(2, 1, vec![255, 0, 0, 0, 255, 0]).unwrap(),
+    ///     0.0, 1.0, 0.0
+    /// );
+    /// assert_eq!(image.data(), &[0, 255]);
+    /// ```
+    pub fn from_rgb8_weighted(
+        image: &RgbImage,
+        r_weight: f32,
+        g_weight: f32,
+        b_weight: f32
+    ) -> Self
     {
-        Self::create_image(image.dimensions(), imageops::grayscale(image).into_raw().into())
+        Self::create_image(
+            image.dimensions(),
+            image.pixels()
+                .map(|pixel| {
+                    let luma = f32::from(pixel[0]) * r_weight
+                        + f32::from(pixel[1]) * g_weight
+                        + f32::from(pixel[2]) * b_weight;
+                    luma.round().max(0.0).min(255.0) as u8
+                })
+                .collect()
+        )
     }
 
     fn create_image(dimensions: (u32, u32), data: Vec<u8>) -> Self {
@@ -295,29 +1232,171 @@ impl ZBarImage<Vec<u8>> {
 #[cfg(feature = "from_image")]
 impl From<DynamicImage> for ZBarImage<Vec<u8>> {
     fn from(image: DynamicImage) -> Self {
-        Self::create_image(image.dimensions(), match image {
-            DynamicImage::ImageLuma8(image) => image,
-            other                           => other.to_luma()
-        }.into_raw())
+        let dimensions = image.dimensions();
+        match image {
+            DynamicImage::ImageLuma8(image)  => Self::create_image(dimensions, image.into_raw()),
+            // keep the upper 8 bits like from_luma16 instead of image's rounding conversion
+            DynamicImage::ImageLuma16(image) => {
+                Self::from_luma16(dimensions.0, dimensions.1, image.as_raw()).unwrap()
+            }
+            other => Self::create_image(dimensions, other.to_luma8().into_raw()),
+        }
+    }
+}
+
+/// Builds a `ZBarImage` with its sequence number, crop and userdata set in one go.
+///
+/// Created by `ZBarImage::builder`. Unlike the scanner and processor builders the methods take
+/// the builder by value, as `build` moves the data into the image.
+pub struct ImageBuilder<T> {
+    width: u32,
+    height: u32,
+    format: Format,
+    data: Option<T>,
+    sequence: Option<u32>,
+    userdata: *mut c_void,
+    #[cfg(feature = "zbar_fork")]
+    crop: Option<(u32, u32, u32, u32)>,
+}
+impl<T> ImageBuilder<T> where T: AsRef<[u8]> {
+    pub fn new(width: u32, height: u32, format: Format) -> Self {
+        Self {
+            width,
+            height,
+            format,
+            data: None,
+            sequence: None,
+            userdata: ptr::null_mut(),
+            #[cfg(feature = "zbar_fork")]
+            crop: None,
+        }
+    }
+    /// Sets the pixel data. This is required, see `ZBarImage::new` for its length.
+    pub fn with_data(mut self, data: T) -> Self { self.data = Some(data); self }
+    /// Sets the sequence number, e.g. the index of a video frame.
+    pub fn with_sequence(mut self, sequence: u32) -> Self { self.sequence = Some(sequence); self }
+    /// Attaches application data, e.g. for ZBar-based C code receiving the image.
+    ///
+    /// The pointer is neither dereferenced nor freed, see `ZBarImage::userdata`.
+    pub fn with_userdata(mut self, userdata: *mut c_void) -> Self {
+        self.userdata = userdata; self
+    }
+    /// Restricts scanning to the given rectangle.
+    #[cfg(feature = "zbar_fork")]
+    pub fn with_crop(mut self, x: u32, y: u32, width: u32, height: u32) -> Self {
+        self.crop = Some((x, y, width, height)); self
+    }
+
+    /// Creates the image. Returns an error if no data has been set or it doesn't match the
+    /// dimensions.
+    pub fn build(self) -> Result<T> {
+        let image = ZBarImage::new(
+            self.width, self.height, self.format, self.data.ok_or(ZBarImageError::NoData)?
+        )?;
+        if let Some(sequence) = self.sequence {
+            image.set_sequence(sequence);
+        }
+        unsafe { ffi::zbar_image_set_userdata(image.image, self.userdata) };
+        #[cfg(feature = "zbar_fork")]
+        {
+            if let Some((x, y, width, height)) = self.crop {
+                image.set_crop(x, y, width, height);
+            }
+        }
+        Ok(image)
     }
 }
 
 impl<T> Clone for ZBarImage<T> {
     fn clone(&self) -> Self {
-        let image = Self { image: self.image, data: self.data.clone() };
+        let image = Self {
+            image: self.image,
+            data: self.data.clone(),
+            stride: self.stride.clone(),
+        };
         image.set_ref(1);
         image
     }
 }
 
+impl<T> fmt::Debug for ZBarImage<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ZBarImage")
+            .field("width", &self.width())
+            .field("height", &self.height())
+            .field("format", &self.format())
+            .field("sequence", &self.sequence())
+            .finish()
+    }
+}
+
 impl<T> Drop for ZBarImage<T> {
     fn drop(&mut self) { self.set_ref(-1) }
 }
 
+/// A `ZBarImage` whose data is shared through an `Arc`, so it can be moved across threads.
+///
+/// Created by `ZBarImage::into_shared`. Clones share the data and hold one ZBar reference to
+/// the underlying image each. ZBar guards its reference counts with a lock or atomic operations
+/// when built with thread support (the default), so clones may be dropped concurrently.
+pub struct SharedZBarImage<T> {
+    image: *mut ffi::zbar_image_s,
+    data: Arc<T>,
+    stride: Option<u32>,
+}
+impl<T> SharedZBarImage<T> where T: AsRef<[u8]> {
+    pub fn format(&self) -> Format {
+        unsafe { (ffi::zbar_image_get_format(self.image) as u32).into() }
+    }
+    pub fn width(&self) -> u32 { unsafe { ffi::zbar_image_get_width(self.image) } }
+    pub fn height(&self) -> u32 { unsafe { ffi::zbar_image_get_height(self.image) } }
+    pub fn data(&self) -> &[u8] { (*self.data).as_ref() }
+    /// Converts this image back into a `ZBarImage`, e.g. to scan it on the receiving thread.
+    ///
+    /// Returns the image unchanged if it is still shared with clones.
+    pub fn into_image(self) -> ::std::result::Result<ZBarImage<T>, Self> {
+        let (image, stride) = (self.image, self.stride);
+        let data = unsafe { ptr::read(&self.data) };
+        mem::forget(self);
+        match Arc::try_unwrap(data) {
+            Ok(data) => {
+                let data = Rc::new(data);
+                // data stored inline has been moved
+                unsafe { set_data(image, (*data).as_ref()) };
+                Ok(ZBarImage { image, data, stride: Cell::new(stride) })
+            }
+            Err(data) => Err(SharedZBarImage { image, data, stride }),
+        }
+    }
+}
+unsafe impl<T> Send for SharedZBarImage<T> where T: Send + Sync {}
+unsafe impl<T> Sync for SharedZBarImage<T> where T: Send + Sync {}
+impl<T> Clone for SharedZBarImage<T> {
+    fn clone(&self) -> Self {
+        set_ref(self.image, 1);
+        Self { image: self.image, data: self.data.clone(), stride: self.stride }
+    }
+}
+impl<T> fmt::Debug for SharedZBarImage<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SharedZBarImage")
+            .field("width", &unsafe { ffi::zbar_image_get_width(self.image) })
+            .field("height", &unsafe { ffi::zbar_image_get_height(self.image) })
+            .finish()
+    }
+}
+impl<T> Drop for SharedZBarImage<T> {
+    fn drop(&mut self) { set_ref(self.image, -1) }
+}
+
 #[cfg(test)]
 mod test {
     #[cfg(feature = "from_image")]
     use image_crate::ImageBuffer;
+    use format::{
+        NV12,
+        YUYV
+    };
     use super::*;
 
     #[test]
@@ -339,6 +1418,44 @@ mod test {
         assert_eq!(image.data(), &[0; 2 * 3])
     }
 
+    #[test]
+    fn test_clone_owned_drop_original() {
+        let image = ZBarImage::new(2, 3, Y800, vec![1, 2, 3, 4, 5, 6]).unwrap();
+        let clone = image.clone();
+        drop(image);
+        assert_eq!(clone.data(), &[1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_inline_data() {
+        let image = ZBarImage::new(2, 3, Y800, [1_u8, 2, 3, 4, 5, 6]).unwrap();
+        assert_eq!(image.data(), &[1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_from_raw_ptr() {
+        let buf = vec![0, 1, 2, 3, 4, 5];
+        {
+            let image = unsafe { ZBarImage::from_raw_ptr(2, 3, Y800, buf.as_ptr(), buf.len()) }
+                .unwrap();
+            assert_eq!(image.data(), buf.as_slice());
+        }
+        assert_eq!(buf, vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_from_raw_ptr_len_mismatch() {
+        let buf = vec![0; 5];
+        assert!(unsafe { ZBarImage::from_raw_ptr(2, 3, Y800, buf.as_ptr(), buf.len()) }.is_err());
+    }
+
+    #[test]
+    fn test_debug() {
+        let debug = format!("{:?}", ZBarImage::new(2, 3, Y800, vec![0; 2 * 3]).unwrap());
+        assert!(debug.contains("width: 2"));
+        assert!(debug.contains("height: 3"));
+    }
+
     #[test]
     fn format() {
         let format = Format::from_label("Y800");
@@ -362,6 +1479,16 @@ mod test {
         assert_eq!(image.sequence(), 999);
     }
 
+    #[test]
+    fn test_next_sequence() {
+        let image = ZBarImage::new(2, 3, Format::from_label("Y800"), vec![0; 2 * 3])
+            .unwrap();
+        assert_eq!(image.next_sequence(), 1);
+        assert_eq!(image.next_sequence(), 2);
+        assert_eq!(image.next_sequence(), 3);
+        assert_eq!(image.sequence(), 3);
+    }
+
     #[test]
     fn test_set_size_smaller() {
         let image = ZBarImage::new(20, 30, Format::from_label("Y800"), vec![0; 20 * 30])
@@ -407,6 +1534,32 @@ mod test {
         assert_eq!(image.data().as_ref(), buf.as_slice());
     }
 
+    #[test]
+    fn test_data_mut() {
+        let mut image = ZBarImage::new(3, 1, Y800, vec![0, 1, 2]).unwrap();
+        image.data_mut().unwrap().copy_from_slice(&[3, 4, 5]);
+        assert_eq!(image.data(), &[3, 4, 5]);
+
+        let _clone = image.clone();
+        assert!(image.data_mut().is_none());
+    }
+
+    #[test]
+    fn test_content_hash() {
+        let image = ZBarImage::new(2, 3, Y800, vec![0, 1, 2, 3, 4, 5]).unwrap();
+        let same = ZBarImage::new(2, 3, Y800, vec![0, 1, 2, 3, 4, 5]).unwrap();
+        let changed = ZBarImage::new(2, 3, Y800, vec![0, 1, 2, 3, 4, 6]).unwrap();
+        assert_eq!(image.content_hash(), same.content_hash());
+        assert_ne!(image.content_hash(), changed.content_hash());
+    }
+
+    #[test]
+    fn test_data_len() {
+        let image = ZBarImage::new(3, 4, Format::from_label("Y800"), vec![0; 3 * 4]).unwrap();
+        assert_eq!(image.data_len(), 3 * 4);
+        assert_eq!(image.data_len(), image.data().len());
+    }
+
     #[test]
     fn test_symbols_get_and_set() {
         let image = ZBarImage::new(20, 30, Format::from_label("Y800"), vec![0; 20 * 30])
@@ -479,10 +1632,458 @@ mod test {
         assert_eq!(image.crop(), (5, 30, 15, 0));
     }
 
+    #[test]
+    fn test_builder() {
+        let image = ZBarImage::builder(20, 30, Y800)
+            .with_data(vec![0; 20 * 30])
+            .with_sequence(7)
+            .build()
+            .unwrap();
+        assert_eq!(image.sequence(), 7);
+        assert_eq!(image.width(), 20);
+        assert_eq!(image.height(), 30);
+    }
+
+    #[test]
+    #[cfg(feature = "zbar_fork")]
+    fn test_builder_crop() {
+        let image = ZBarImage::builder(20, 30, Y800)
+            .with_data(vec![0; 20 * 30])
+            .with_sequence(3)
+            .with_crop(5, 5, 10, 10)
+            .build()
+            .unwrap();
+        assert_eq!(image.sequence(), 3);
+        assert_eq!(image.crop(), (5, 5, 10, 10));
+    }
+
+    #[test]
+    fn test_builder_userdata() {
+        let mut userdata = 42;
+        let image = ZBarImage::builder(2, 3, Y800)
+            .with_data(vec![0; 2 * 3])
+            .with_userdata(&mut userdata as *mut i32 as *mut c_void)
+            .build()
+            .unwrap();
+        assert_eq!(unsafe { *(image.userdata() as *const i32) }, 42);
+        assert!(ZBarImage::new(2, 3, Y800, vec![0; 2 * 3]).unwrap().userdata().is_null());
+    }
+
+    #[test]
+    fn test_builder_no_data() {
+        assert!(ZBarImage::<Vec<u8>>::builder(2, 3, Y800).build().is_err());
+    }
+
+    #[test]
+    fn test_from_luma16() {
+        let image = ZBarImage::from_luma16(2, 2, &[0x0000, 0x00ff, 0x8000, 0xffff]).unwrap();
+        assert_eq!(image.data(), &[0x00, 0x00, 0x80, 0xff]);
+    }
+
+    #[test]
+    fn test_from_luma16_shifted() {
+        let image = ZBarImage::from_luma16_shifted(2, 2, &[0x0000, 0x00ff, 0x0400, 0xffff], 4)
+            .unwrap();
+        assert_eq!(image.data(), &[0x00, 0x0f, 0x40, 0xff]);
+    }
+
+    #[test]
+    fn test_pixel() {
+        let image = ZBarImage::new(4, 2, Y800, (0..8).map(|i| i * 32).collect::<Vec<u8>>())
+            .unwrap();
+        assert_eq!(image.pixel(0, 0), Some(0));
+        assert_eq!(image.pixel(2, 1), Some(192));
+        assert_eq!(image.pixel(4, 0), None);
+        assert_eq!(image.pixel(0, 2), None);
+    }
+
+    #[test]
+    fn test_luma_histogram() {
+        let image = ZBarImage::new(4, 2, Y800, vec![0, 0, 16, 16, 16, 128, 255, 255]).unwrap();
+        let histogram = image.luma_histogram();
+        assert_eq!(histogram[0], 2);
+        assert_eq!(histogram[16], 3);
+        assert_eq!(histogram[128], 1);
+        assert_eq!(histogram[255], 2);
+        assert_eq!(histogram.iter().sum::<u32>(), 8);
+    }
+
+    #[test]
+    fn test_has_sufficient_contrast() {
+        let flat = ZBarImage::new(2, 2, Y800, vec![128; 4]).unwrap();
+        assert!(!flat.has_sufficient_contrast(0));
+
+        let contrast = ZBarImage::new(2, 2, Y800, vec![10, 240, 10, 240]).unwrap();
+        assert!(contrast.has_sufficient_contrast(200));
+        assert!(!contrast.has_sufficient_contrast(230));
+    }
+
+    #[test]
+    fn test_rotated_90() {
+        let image = ZBarImage::new(2, 3, Y800, vec![1, 2, 3, 4, 5, 6]).unwrap();
+        let rotated = image.rotated_90();
+        assert_eq!((rotated.width(), rotated.height()), (3, 2));
+        assert_eq!(rotated.format(), Y800);
+        assert_eq!(rotated.data(), &[5, 3, 1, 6, 4, 2]);
+    }
+
+    #[test]
+    fn test_flipped() {
+        let image = ZBarImage::new(2, 3, Y800, vec![1, 2, 3, 4, 5, 6]).unwrap();
+
+        let flipped = image.flipped_h();
+        assert_eq!((flipped.width(), flipped.height()), (2, 3));
+        assert_eq!(flipped.format(), Y800);
+        assert_eq!(flipped.data(), &[2, 1, 4, 3, 6, 5]);
+
+        let flipped = image.flipped_v();
+        assert_eq!((flipped.width(), flipped.height()), (2, 3));
+        assert_eq!(flipped.data(), &[5, 6, 3, 4, 1, 2]);
+    }
+
+    #[test]
+    fn test_padded() {
+        let image = ZBarImage::new(2, 2, Y800, vec![1, 2, 3, 4]).unwrap();
+        let padded = image.padded(4, 4, 255);
+        assert_eq!((padded.width(), padded.height()), (4, 4));
+        assert_eq!(padded.format(), Y800);
+        assert_eq!(
+            padded.data(),
+            &[
+                255, 255, 255, 255,
+                255,   1,   2, 255,
+                255,   3,   4, 255,
+                255, 255, 255, 255,
+            ][..]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_padded_smaller() {
+        ZBarImage::new(2, 2, Y800, vec![1, 2, 3, 4]).unwrap().padded(1, 4, 255);
+    }
+
+    #[test]
+    fn test_transforms_convert_to_y800() {
+        // 2x1 pixels: Y0 U Y1 V
+        let image = ZBarImage::new(2, 1, YUYV, vec![16, 128, 235, 128]).unwrap();
+
+        let rotated = image.rotated_90();
+        assert_eq!(rotated.format(), Y800);
+        assert_eq!((rotated.width(), rotated.height()), (1, 2));
+        assert_eq!(rotated.data(), &[16, 235]);
+
+        assert_eq!(image.flipped_h().data(), &[235, 16]);
+        assert_eq!(image.flipped_v().data(), &[16, 235]);
+        assert_eq!(image.padded(4, 1, 255).data(), &[255, 16, 235, 255]);
+    }
+
+    #[test]
+    fn test_new_with_cleanup() {
+        use std::cell::Cell;
+
+        let cleaned_up = Rc::new(Cell::new(false));
+        let buf = vec![1, 2];
+        let (ptr, len) = (buf.as_ptr(), buf.len());
+        let image = {
+            let cleaned_up = cleaned_up.clone();
+            unsafe {
+                ZBarImage::new_with_cleanup(2, 1, Y800, ptr, len, move || {
+                    drop(buf);
+                    cleaned_up.set(true);
+                })
+            }.unwrap()
+        };
+        let clone = image.clone();
+        assert_eq!(clone.data(), &[1, 2]);
+
+        drop(image);
+        assert!(!cleaned_up.get());
+        drop(clone);
+        assert!(cleaned_up.get());
+    }
+
+    #[test]
+    fn test_from_strided() {
+        let data = [
+            1, 2, 3, 0,
+            4, 5, 6, 0,
+            7, 8, 9,
+        ];
+        let image = ZBarImage::from_strided(3, 3, 4, &data).unwrap();
+        assert_eq!((image.width(), image.height()), (3, 3));
+        assert_eq!(image.pixel(0, 1), Some(4));
+        assert_eq!(image.pixel(2, 1), Some(6));
+        assert_eq!(image.pixel(2, 2), Some(9));
+        assert_eq!(image.pixel(3, 1), None);
+
+        match ZBarImage::from_strided(3, 3, 2, &data) {
+            Err(ZBarImageError::Len(3, 3, 11)) => {},
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(ZBarImage::from_strided(3, 3, 4, &data[..10]).is_err());
+    }
+
+    #[test]
+    fn test_stride() {
+        let data = vec![
+            1, 2, 3, 0,
+            4, 5, 6, 0,
+            7, 8, 9,
+        ];
+        let image = ZBarImage::new_strided(3, 3, 4, data).unwrap();
+        assert_eq!(image.stride(), 4);
+        assert_eq!(image.pixel(0, 1), Some(4));
+        assert_eq!(image.pixel(2, 1), Some(6));
+        assert_eq!(image.pixel(2, 2), Some(9));
+        assert_eq!(image.pixel(3, 1), None);
+        assert_eq!(
+            image.rows().collect::<Vec<_>>(),
+            vec![&[1, 2, 3][..], &[4, 5, 6][..], &[7, 8, 9][..]]
+        );
+        assert_eq!(image.luma_histogram()[0], 0);
+        assert_eq!(image.clone().stride(), 4);
+        assert_eq!(image.packed().unwrap().data(), &[1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+        let image = ZBarImage::new(2, 2, Y800, vec![1, 2, 3, 4]).unwrap();
+        assert_eq!(image.stride(), 2);
+        assert!(image.packed().is_none());
+
+        match ZBarImage::new_strided(3, 3, 4, vec![0; 10]) {
+            Err(ZBarImageError::Len(3, 3, 10)) => {},
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_set_stride() {
+        let image = ZBarImage::new_strided(2, 2, 2, vec![1, 2, 0, 3, 4]).unwrap();
+        assert_eq!(image.pixel(0, 1), Some(0));
+        image.set_stride(3).unwrap();
+        assert_eq!(image.stride(), 3);
+        assert_eq!(image.pixel(0, 1), Some(3));
+        assert_eq!(image.pixel(1, 1), Some(4));
+    }
+
+    #[test]
+    fn test_set_stride_invalid() {
+        let image = ZBarImage::new(2, 2, Y800, vec![1, 2, 3, 4]).unwrap();
+        match image.set_stride(3) {
+            Err(ZBarImageError::Len(2, 2, 4)) => {},
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match image.set_stride(1) {
+            Err(ZBarImageError::Len(2, 2, 4)) => {},
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(image.stride(), 2);
+
+        let image = ZBarImage::new(2, 1, YUYV, vec![16, 128, 235, 128]).unwrap();
+        match image.set_stride(4) {
+            Err(ZBarImageError::NotGrayscale(format)) => assert_eq!(format, YUYV),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_packed_keeps_attributes() {
+        let mut userdata = 42;
+        let image = ZBarImage::new_strided(2, 2, 3, vec![1, 2, 0, 3, 4]).unwrap();
+        image.set_sequence(7);
+        unsafe {
+            ffi::zbar_image_set_userdata(image.image, &mut userdata as *mut i32 as *mut c_void)
+        };
+        let packed = image.packed().unwrap();
+        assert_eq!(packed.data(), &[1, 2, 3, 4]);
+        assert_eq!(packed.sequence(), 7);
+        assert_eq!(packed.userdata(), image.userdata());
+    }
+
+    #[test]
+    #[cfg(feature = "zbar_fork")]
+    fn test_packed_keeps_crop() {
+        let image = ZBarImage::new_strided(4, 2, 5, vec![0; 5 + 4]).unwrap();
+        image.set_crop(1, 0, 2, 2);
+        assert_eq!(image.packed().unwrap().crop(), (1, 0, 2, 2));
+    }
+
+    #[test]
+    fn test_dimension_overflow() {
+        match ZBarImage::new(u32::max_value(), 2, Y800, vec![0; 2]) {
+            Err(ZBarImageError::DimensionOverflow(w, 2)) => assert_eq!(w, u32::max_value()),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_new_yuv() {
+        assert!(ZBarImage::new(4, 2, YUYV, vec![0; 4 * 2 * 2]).is_ok());
+        assert!(ZBarImage::new(4, 2, YUYV, vec![0; 4 * 2]).is_err());
+        assert!(ZBarImage::new(4, 2, NV12, vec![0; 4 * 2 + 4]).is_ok());
+    }
+
+    #[test]
+    fn test_yuyv_one_byte_per_pixel() {
+        // accepted before YUYV buffers were checked for 2 bytes per pixel
+        let data = vec![0; 4 * 2];
+        match ZBarImage::new(4, 2, YUYV, data.clone()) {
+            Err(ZBarImageError::Len(4, 2, 8)) => {},
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match unsafe { ZBarImage::from_raw_ptr(4, 2, YUYV, data.as_ptr(), data.len()) } {
+            Err(ZBarImageError::Len(4, 2, 8)) => {},
+            other => panic!("unexpected result: {:?}", other),
+        }
+        // the length ZBar reads for a 4x2 YUYV frame
+        assert!(ZBarImage::new(4, 2, YUYV, vec![0; 4 * 2 * 2]).is_ok());
+    }
+
+    #[test]
+    fn test_convert() {
+        let yuyv = ZBarImage::new(2, 1, YUYV, vec![16, 128, 235, 128]).unwrap();
+        let grey = yuyv.convert(Y800).unwrap();
+        assert_eq!((grey.width(), grey.height(), grey.format()), (2, 1, Y800));
+        assert_eq!(grey.data(), &[16, 235]);
+    }
+
+    #[test]
+    fn test_into_shared() {
+        use std::thread;
+
+        let image = ZBarImage::new(2, 1, Y800, [1_u8, 2]).unwrap().into_shared().unwrap();
+        let clone = image.clone();
+        let data = thread::spawn(move || clone.data().to_vec()).join().unwrap();
+        assert_eq!(data, vec![1, 2]);
+
+        let image = image.into_image().unwrap();
+        assert_eq!(image.data(), &[1, 2]);
+    }
+
+    #[test]
+    fn test_into_inner() {
+        let image = ZBarImage::new(2, 3, Y800, vec![1, 2, 3, 4, 5, 6]).unwrap();
+        assert_eq!(image.into_inner(), Some(vec![1, 2, 3, 4, 5, 6]));
+
+        let image = ZBarImage::new(2, 1, Y800, vec![1, 2]).unwrap();
+        let clone = image.clone();
+        assert!(image.into_inner().is_none());
+        assert_eq!(clone.into_inner(), Some(vec![1, 2]));
+    }
+
+    #[test]
+    fn test_into_shared_cloned() {
+        let image = ZBarImage::new(2, 1, Y800, vec![1, 2]).unwrap();
+        let _clone = image.clone();
+        assert!(image.into_shared().is_err());
+    }
+
+    #[test]
+    fn test_zero_dimension() {
+        match ZBarImage::new(0, 5, Y800, Vec::<u8>::new()) {
+            Err(ZBarImageError::ZeroDimension(0, 5)) => {},
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
     #[test]
     #[cfg(feature = "from_image")]
     fn test_from_path() { assert!(ZBarImage::from_path("test/code128.gif").is_ok()); }
 
+    #[test]
+    #[cfg(feature = "from_image")]
+    fn test_from_path_respecting_orientation() {
+        use prelude::{
+            ZBarConfig,
+            ZBarImageScanner,
+            ZBarSymbolType
+        };
+
+        // only scan horizontally, so the stored sideways code can't be decoded
+        let scanner = ZBarImageScanner::builder()
+            .with_config(ZBarSymbolType::ZBAR_CODE128, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .with_config(ZBarSymbolType::ZBAR_NONE, ZBarConfig::ZBAR_CFG_Y_DENSITY, 0)
+            .build()
+            .unwrap();
+
+        let stored = ZBarImage::from_path("test/code128_exif_rotate90.png").unwrap();
+        assert_eq!((stored.width(), stored.height()), (80, 260));
+        assert!(scanner.scan_image(&stored).unwrap().first_symbol().is_none());
+
+        let upright =
+            ZBarImage::from_path_respecting_orientation("test/code128_exif_rotate90.png").unwrap();
+        assert_eq!((upright.width(), upright.height()), (260, 80));
+        let symbol = scanner.scan_image(&upright).unwrap().first_symbol().unwrap();
+        assert_eq!(symbol.data(), "Hello");
+
+        // no EXIF data
+        let image = ZBarImage::from_path_respecting_orientation("test/code128.gif").unwrap();
+        assert_eq!(image.data(), ZBarImage::from_path("test/code128.gif").unwrap().data());
+    }
+
+    #[test]
+    #[cfg(feature = "from_image")]
+    fn test_oriented() {
+        let image = DynamicImage::ImageLuma8(ImageBuffer::from_vec(2, 1, vec![1, 2]).unwrap());
+        assert_eq!(oriented(image.clone(), 1).into_bytes(), vec![1, 2]);
+        assert_eq!(oriented(image.clone(), 2).into_bytes(), vec![2, 1]);
+        let rotated = oriented(image.clone(), 6);
+        assert_eq!((rotated.width(), rotated.height()), (1, 2));
+        assert_eq!(rotated.into_bytes(), vec![1, 2]);
+        assert_eq!(oriented(image, 8).into_bytes(), vec![2, 1]);
+    }
+
+    #[test]
+    #[cfg(feature = "from_image")]
+    fn test_from_gif_frames() {
+        let frames = ZBarImage::from_gif_frames("test/frames.gif").unwrap();
+        assert_eq!(frames.len(), 3);
+        frames.iter().for_each(|frame| {
+            assert_eq!((frame.width(), frame.height()), (4, 4));
+            assert_eq!(frame.format(), Y800);
+        });
+        assert_eq!(frames[0].data(), &[0; 4 * 4][..]);
+        assert_eq!(frames[1].data(), &[255; 4 * 4][..]);
+
+        assert!(ZBarImage::from_gif_frames("test/missing.gif").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "from_image")]
+    fn test_from_reader() {
+        use prelude::{
+            ZBarConfig,
+            ZBarImageScanner,
+            ZBarSymbolType
+        };
+        use std::{
+            fs,
+            io::Cursor
+        };
+
+        let bytes = fs::read("test/qr_hello-world.png").unwrap();
+        let image = ZBarImage::from_reader(Cursor::new(bytes), ImageFormat::Png).unwrap();
+        let scanner = ZBarImageScanner::builder()
+            .with_config(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .build()
+            .unwrap();
+        scanner.scan_image(&image).unwrap();
+        assert_eq!(image.first_symbol().unwrap().data(), "Hello World");
+
+        assert!(ZBarImage::from_reader(Cursor::new(vec![0; 4]), ImageFormat::Png).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "from_image")]
+    fn test_from_dyn_image_luma16() {
+        let data = vec![0x00ff, 0x80ff];
+        let image = ZBarImage::from_dyn_image(
+            DynamicImage::ImageLuma16(ImageBuffer::from_vec(2, 1, data).unwrap())
+        );
+        assert_eq!(image.format(), Y800);
+        assert_eq!(image.data(), &[0x00, 0x80]);
+    }
+
     #[test]
     #[cfg(feature = "from_image")]
     fn test_from_dyn_image_luma() {
@@ -503,6 +2104,72 @@ mod test {
         assert_eq!(image.data(), &[0]);
     }
 
+    #[test]
+    #[cfg(feature = "from_image")]
+    fn test_from_dyn_image_rgb_white() {
+        let data = vec![255, 255, 255, 0, 0, 0];
+        let image = ZBarImage::from_dyn_image(
+            DynamicImage::ImageRgb8(ImageBuffer::from_vec(2, 1, data).unwrap())
+        );
+        assert_eq!(image.data(), &[255, 0]);
+    }
+
+    #[test]
+    #[cfg(feature = "nokhwa")]
+    fn test_from_nokhwa_frame() {
+        use nokhwa::{
+            Buffer,
+            utils::{
+                FrameFormat,
+                Resolution
+            }
+        };
+
+        let data = [0, 64, 128, 192, 255, 32];
+        let frame = Buffer::new(Resolution::new(3, 2), &data, FrameFormat::GRAY);
+        let image = ZBarImage::from_nokhwa_frame(&frame).unwrap();
+        assert_eq!((image.width(), image.height()), (3, 2));
+        assert_eq!(image.data(), &data);
+    }
+
+    #[test]
+    #[cfg(feature = "from_image")]
+    fn test_from_rgba8_with_background() {
+        let data = vec![
+            0, 0, 0, 255,
+            0, 0, 0, 128,
+            0, 0, 0, 0,
+            255, 255, 255, 0,
+        ];
+        let rgba = ImageBuffer::from_vec(4, 1, data).unwrap();
+        assert_eq!(ZBarImage::from_rgba8_with_background(&rgba, 255).data(), &[0, 127, 255, 255]);
+        assert_eq!(ZBarImage::from_rgba8_with_background(&rgba, 0).data(), &[0, 0, 0, 0]);
+    }
+
+    #[test]
+    #[cfg(feature = "from_image")]
+    fn test_from_rgb8_weighted() {
+        use prelude::{
+            ZBarConfig,
+            ZBarImageScanner,
+            ZBarSymbolType
+        };
+
+        let scanner = ZBarImageScanner::builder()
+            .with_config(ZBarSymbolType::ZBAR_EAN13, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .build()
+            .unwrap();
+
+        // red bars and the green background have about the same standard luminance
+        let standard = ZBarImage::from_path("test/ean13_red_on_green.png").unwrap();
+        assert!(scanner.scan_image(&standard).unwrap().first_symbol().is_none());
+
+        let rgb = image_crate::open("test/ean13_red_on_green.png").unwrap().to_rgb8();
+        let weighted = ZBarImage::from_rgb8_weighted(&rgb, 0.0, 3.0, 0.0);
+        let symbol = scanner.scan_image(&weighted).unwrap().first_symbol().unwrap();
+        assert_eq!(symbol.data(), "4006381333931");
+    }
+
     #[test]
     #[cfg(feature = "from_image")]
     fn test_from_generic_image_luma() {
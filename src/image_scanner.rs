@@ -1,32 +1,353 @@
 use {
+    all_symbol_types,
+    config::Config,
     ffi,
-    image::ZBarImage,
-    symbol_set::ZBarSymbolSet,
+    parse_config,
+    format::{
+        Format,
+        NV12,
+        Y800,
+        YUYV
+    },
+    image::{
+        ZBarImage,
+        ZBarImageError
+    },
+    symbol::{
+        OwnedSymbol,
+        SymbolData,
+        TextPolicy,
+        ZBarSymbol
+    },
+    symbol_set::{
+        collect_unique,
+        ZBarSymbolSet
+    },
     ZBarConfig,
+    ZBarError,
     ZBarErrorType,
     ZBarResult,
     ZBarSymbolType
 };
-use std::ptr;
+#[cfg(feature = "from_image")]
+use image_crate::ImageError;
+use std::{
+    cell::{
+        Cell,
+        RefCell
+    },
+    collections::HashMap,
+    fmt,
+    fs,
+    ops::Deref,
+    path::{
+        Path,
+        PathBuf
+    },
+    ptr,
+    str::Utf8Error,
+    sync::{
+        Condvar,
+        Mutex,
+        MutexGuard,
+        PoisonError
+    },
+    time::{
+        Duration,
+        Instant
+    },
+};
+
+const RETAIL_SYMBOL_TYPES: [ZBarSymbolType; 5] = [
+    ZBarSymbolType::ZBAR_EAN13,
+    ZBarSymbolType::ZBAR_EAN8,
+    ZBarSymbolType::ZBAR_UPCA,
+    ZBarSymbolType::ZBAR_UPCE,
+    ZBarSymbolType::ZBAR_CODE128,
+];
+
+/// Creates a `ZBarImageScanner` with the common retail symbologies enabled.
+///
+/// These are EAN-13, EAN-8, UPC-A, UPC-E and CODE-128.
+///
+/// # Examples
+///
+/// ```
+/// use zbars::image_scanner::retail_scanner;
+///
+/// let scanner = retail_scanner().unwrap();
+/// ```
+pub fn retail_scanner() -> ZBarResult<ZBarImageScanner> {
+    ImageScannerBuilder::new().with_retail_symbologies().build()
+}
+
+/// Scans all images at the given paths reusing a single scanner with the given configs.
+///
+/// Files that can't be loaded don't stop the scan. Their error is recorded instead:
+/// `ZBAR_ERR_SYSTEM` if the file can't be read and `ZBAR_ERR_UNSUPPORTED` if it can't be
+/// decoded as image.
+///
+/// # Examples
+///
+/// ```
+/// use zbars::image_scanner::scan_paths;
+/// use zbars::prelude::*;
+///
+/// let results = scan_paths(
+///     &["test/qr_hello-world.png", "test/code128.gif"],
+///     &[
+///         (ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1),
+///         (ZBarSymbolType::ZBAR_CODE128, ZBarConfig::ZBAR_CFG_ENABLE, 1),
+///     ]
+/// );
+/// for (path, symbols) in results {
+///     println!("{}: {:?}", path.display(), symbols);
+/// }
+/// ```
+#[cfg(feature = "from_image")]
+pub fn scan_paths<P: AsRef<Path>>(
+    paths: &[P],
+    configs: &[(ZBarSymbolType, ZBarConfig, i32)]
+) -> Vec<(PathBuf, ZBarResult<Vec<OwnedSymbol>>)>
+{
+    let mut builder = ImageScannerBuilder::new();
+    configs.iter().for_each(|&(symbol_type, config, value)| {
+        builder.with_config(symbol_type, config, value);
+    });
+    let scanner = builder.build();
+
+    paths
+        .iter()
+        .map(|path| {
+            let result = scanner.as_ref().map_err(|e| *e).and_then(|scanner| {
+                let image = ZBarImage::from_path(path).map_err(|e| match e {
+                    ImageError::IoError(_) => ZBarErrorType::Complex(ZBarError::ZBAR_ERR_SYSTEM),
+                    _ => ZBarErrorType::Complex(ZBarError::ZBAR_ERR_UNSUPPORTED),
+                })?;
+                scanner
+                    .scan_image(&image)
+                    .map(|symbols| symbols.iter().map(OwnedSymbol::from).collect())
+            });
+            (path.as_ref().to_path_buf(), result)
+        })
+        .collect()
+}
 
 pub struct ZBarImageScanner {
     pub(crate) scanner: *mut ffi::zbar_image_scanner_s,
+    text_policy: Cell<TextPolicy>,
+    // ZBar offers no config getter, so every applied config is recorded here.
+    config: RefCell<HashMap<(ZBarSymbolType, ZBarConfig), i32>>,
+    cache: Cell<bool>,
 }
 impl ZBarImageScanner {
     pub fn new() -> Self { Self::default() }
     pub fn builder() -> ImageScannerBuilder { ImageScannerBuilder::new() }
+    /// Returns the underlying ZBar image scanner for interop with other ZBar-based C code.
+    ///
+    /// The pointer is owned by this `ZBarImageScanner` and only valid as long as it is alive.
+    /// Callers must not destroy it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::ffi;
+    /// use zbars::prelude::*;
+    ///
+    /// let scanner = ZBarImageScanner::new();
+    /// let raw: *mut ffi::zbar_image_scanner_s = scanner.as_raw();
+    /// assert!(!raw.is_null());
+    /// ```
+    pub fn as_raw(&self) -> *mut ffi::zbar_image_scanner_s { self.scanner }
+    /// Applies a config to the given symbology.
+    ///
+    /// Configs can be changed through a shared reference at any time, e.g. between two scans.
+    /// `ZBarImageScanner` is `Send` but not `Sync`, so sharing a scanner between threads
+    /// requires external synchronization like a `Mutex`, which also serializes config changes
+    /// with running scans.
     pub fn set_config(
         &self, symbol_type: ZBarSymbolType,
         config: ZBarConfig,
         value: i32) -> ZBarResult<()>
     {
         match unsafe { ffi::zbar_image_scanner_set_config(self.scanner, symbol_type, config, value) } {
-            0 => Ok(()),
+            0 => {
+                self.record_config(symbol_type, config, value);
+                Ok(())
+            }
             e => Err(e.into())
         }
     }
+    /// Returns the value last applied for `config` of `symbol_type` or `None` if it has never
+    /// been set through this scanner.
+    ///
+    /// ZBar itself offers no way to query configs, so only values set through this crate are
+    /// known. Decoder configs like `ZBAR_CFG_ENABLE` applied to `ZBAR_NONE` are recorded for
+    /// every symbology.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    ///
+    /// let scanner = ZBarImageScanner::new();
+    /// scanner.enable(ZBarSymbolType::ZBAR_QRCODE).unwrap();
+    /// assert_eq!(
+    ///     scanner.config_value(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE),
+    ///     Some(1)
+    /// );
+    /// ```
+    pub fn config_value(&self, symbol_type: ZBarSymbolType, config: ZBarConfig) -> Option<i32> {
+        self.config.borrow().get(&(symbol_type, config)).cloned()
+    }
+    /// Returns the symbologies that are enabled through `ZBAR_CFG_ENABLE`.
+    ///
+    /// Like `config_value` this only knows about configs set through this scanner.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    ///
+    /// let scanner = ZBarImageScanner::new();
+    /// scanner.enable(ZBarSymbolType::ZBAR_QRCODE).unwrap();
+    /// assert_eq!(scanner.enabled_symbologies(), vec![ZBarSymbolType::ZBAR_QRCODE]);
+    /// ```
+    pub fn enabled_symbologies(&self) -> Vec<ZBarSymbolType> {
+        all_symbol_types()
+            .iter()
+            .cloned()
+            .filter(|symbol_type| {
+                self.config_value(*symbol_type, ZBarConfig::ZBAR_CFG_ENABLE)
+                    .map_or(false, |value| value != 0)
+            })
+            .collect()
+    }
+    fn record_config(&self, symbol_type: ZBarSymbolType, config: ZBarConfig, value: i32) {
+        let mut configs = self.config.borrow_mut();
+        // ZBar applies decoder configs for ZBAR_NONE to all symbologies
+        if symbol_type == ZBarSymbolType::ZBAR_NONE
+            && (config as i32) < ZBarConfig::ZBAR_CFG_POSITION as i32
+        {
+            all_symbol_types().iter().for_each(|symbol_type| {
+                configs.insert((*symbol_type, config), value);
+            });
+        }
+        configs.insert((symbol_type, config), value);
+    }
+    /// Enables decoding of the given symbology.
+    pub fn enable(&self, symbol_type: ZBarSymbolType) -> ZBarResult<()> {
+        self.set_config(symbol_type, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+    }
+    /// Disables decoding of the given symbology.
+    pub fn disable(&self, symbol_type: ZBarSymbolType) -> ZBarResult<()> {
+        self.set_config(symbol_type, ZBarConfig::ZBAR_CFG_ENABLE, 0)
+    }
+    /// Like `set_config`, but takes a typed `Config`.
+    pub fn set_config_typed(&self, symbol_type: ZBarSymbolType, config: Config) -> ZBarResult<()> {
+        let (config, value) = config.into_raw();
+        self.set_config(symbol_type, config, value)
+    }
+    /// Sets whether an optional check digit is verified (`ZBAR_CFG_ADD_CHECK`).
+    ///
+    /// Honored by symbologies with an optional check digit like I25, CODE-39 and Codabar.
+    pub fn set_add_check(&self, symbol_type: ZBarSymbolType, enabled: bool) -> ZBarResult<()> {
+        self.set_config(symbol_type, ZBarConfig::ZBAR_CFG_ADD_CHECK, enabled as i32)
+    }
+    /// Sets whether the check digit is part of the decoded data (`ZBAR_CFG_EMIT_CHECK`).
+    ///
+    /// Honored by EAN/UPC, I25, CODE-39 and Codabar. It is enabled by default for EAN/UPC.
+    pub fn set_emit_check(&self, symbol_type: ZBarSymbolType, enabled: bool) -> ZBarResult<()> {
+        self.set_config(symbol_type, ZBarConfig::ZBAR_CFG_EMIT_CHECK, enabled as i32)
+    }
+    /// Sets whether EAN-13 codes of books are reported as ISBN-10 and ISBN-13.
+    pub fn set_isbn_conversion(&self, enabled: bool) -> ZBarResult<()> {
+        self.set_config(ZBarSymbolType::ZBAR_ISBN10, ZBarConfig::ZBAR_CFG_ENABLE, enabled as i32)
+            .and_then(|_| {
+                self.set_config(
+                    ZBarSymbolType::ZBAR_ISBN13, ZBarConfig::ZBAR_CFG_ENABLE, enabled as i32
+                )
+            })
+    }
+    /// Applies a file of newline separated config strings like `qrcode.enable=1`.
+    ///
+    /// Every line is parsed with `parse_config`, blank lines and lines starting with `#` are
+    /// skipped. Stops at the first line that can't be parsed or applied.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use zbars::prelude::*;
+    ///
+    /// let scanner = ZBarImageScanner::new();
+    /// scanner.apply_config_file("zbar.conf").unwrap();
+    /// ```
+    pub fn apply_config_file(&self, path: impl AsRef<Path>) -> ZBarResult<()> {
+        fs::read_to_string(path)
+            .map_err(|_| ZBarErrorType::Complex(ZBarError::ZBAR_ERR_SYSTEM))?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .try_for_each(|line| {
+                parse_config(line)
+                    .and_then(|(symbol_type, config, value)| {
+                        self.set_config(symbol_type, config, value)
+                    })
+            })
+    }
+    /// Restricts the accepted data length (`ZBAR_CFG_MIN_LEN` and `ZBAR_CFG_MAX_LEN`).
+    ///
+    /// Symbols whose data is shorter than `min` or longer than `max` characters are dropped.
+    /// This is useful for rejecting noise on variable length symbologies like CODE-39, CODE-128
+    /// or I25. A `max` of `0` means unlimited.
+    pub fn set_length_range(
+        &self,
+        symbol_type: ZBarSymbolType,
+        min: i32,
+        max: i32
+    ) -> ZBarResult<()>
+    {
+        self.set_config(symbol_type, ZBarConfig::ZBAR_CFG_MIN_LEN, min)
+            .and_then(|_| self.set_config(symbol_type, ZBarConfig::ZBAR_CFG_MAX_LEN, max))
+    }
+    /// Sets how many consistent reads of a symbol are required before it is reported
+    /// (`ZBAR_CFG_UNCERTAINTY`).
+    ///
+    /// Higher values reduce false positives at the cost of latency. Together with the result
+    /// cache this also affects how quickly a symbol is reported again in video streams, as
+    /// every repeated read has to reach the required confidence first.
+    #[cfg(feature = "zbar_fork")]
+    pub fn set_uncertainty(&self, symbol_type: ZBarSymbolType, frames: i32) -> ZBarResult<()> {
+        self.set_config(symbol_type, ZBarConfig::ZBAR_CFG_UNCERTAINTY, frames)
+    }
+    /// Returns the `TextPolicy` used by `symbol_data`.
+    pub fn text_policy(&self) -> TextPolicy { self.text_policy.get() }
+    pub fn set_text_policy(&self, text_policy: TextPolicy) { self.text_policy.set(text_policy); }
+    /// Returns the decoded data of a `Symbol` handled according to this scanner's `TextPolicy`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    /// use zbars::symbol::{SymbolData, TextPolicy};
+    ///
+    /// let image = ZBarImage::new(1, 1, Format::from_label("Y8"), vec![1]).unwrap();
+    /// let scanner = ZBarImageScanner::builder()
+    ///     .with_text_policy(TextPolicy::Lossy)
+    ///     .build()
+    ///     .unwrap();
+    /// for symbol in scanner.scan_image(&image).unwrap().iter() {
+    ///     if let Ok(SymbolData::Text(text)) = scanner.symbol_data(&symbol) {
+    ///         println!("{}", text);
+    ///     }
+    /// }
+    /// ```
+    pub fn symbol_data<'a>(&self, symbol: &'a ZBarSymbol) -> Result<SymbolData<'a>, Utf8Error> {
+        symbol.data_with(self.text_policy.get())
+    }
     pub fn enable_cache(&self, enable: bool) {
         unsafe { ffi::zbar_image_scanner_enable_cache(self.scanner, enable as i32); }
+        self.cache.set(enable);
     }
     pub fn recycle_image<T>(&self, image: &ZBarImage<T>) {
         unsafe { ffi::zbar_image_scanner_recycle_image(self.scanner, image.image()) }
@@ -36,37 +357,346 @@ impl ZBarImageScanner {
             unsafe { ffi::zbar_image_scanner_get_results(self.scanner) }, ptr::null_mut()
         )
     }
+    /// Snapshots the current `results` and starts fresh afterwards.
+    ///
+    /// The results are released and the result cache is flushed, so symbols seen so far are
+    /// reported as new again. Returns `None` if there are no results.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    ///
+    /// let scanner = ZBarImageScanner::builder().with_cache(true).build().unwrap();
+    /// scanner.scan_image(&ZBarImage::new(1, 1, Y800, vec![0]).unwrap()).unwrap();
+    /// if let Some(symbols) = scanner.take_results() {
+    ///     println!("{} symbols seen", symbols.len());
+    /// }
+    /// ```
+    pub fn take_results(&self) -> Option<Vec<OwnedSymbol>> {
+        let symbols = self.results()
+            .map(|symbols| symbols.iter().map(OwnedSymbol::from).collect());
+        // zbar_image_scanner_recycle_image releases the scanner's results whatever image it is
+        // given, and zbar_image_scanner_enable_cache drops all cached symbols before applying
+        // the flag, so passing the current state flushes the cache without changing it
+        self.recycle_image(&ZBarImage::new(1, 1, Y800, [0_u8]).unwrap());
+        unsafe { ffi::zbar_image_scanner_enable_cache(self.scanner, self.cache.get() as i32) };
+        symbols
+    }
+    /// Scans the image for symbols.
+    ///
+    /// ZBar's image scanner only handles 8-bit grayscale formats (`Y800` or `Y8`). Images of
+    /// any other format are converted to `Y800` first and the symbols found are attached to the
+    /// given image. Returns `ZBAR_ERR_UNSUPPORTED` if ZBar can't convert the format or the
+    /// exact size of the format isn't known (see `ZBarImage::convert`). Images with padded rows
+    /// (see `ZBarImage::set_stride`) are scanned from a copy without padding.
     pub fn scan_image<T>(&self, image: &ZBarImage<T>) -> ZBarResult<ZBarSymbolSet> {
+        if !image.format().is_grayscale() {
+            return self.scan_image_converted(image);
+        }
+        if let Some(packed) = image.packed() {
+            return self.scan_image_copy(image, &packed);
+        }
         match unsafe { ffi::zbar_scan_image(self.scanner, image.image()) } {
             -1 => Err(ZBarErrorType::Simple(-1)),
             // symbols can be unwrapped because image is surely scanned
             _  => Ok(image.symbols().unwrap()),
         }
     }
+    fn scan_image_converted<T>(&self, image: &ZBarImage<T>) -> ZBarResult<ZBarSymbolSet> {
+        let converted = image.convert(Y800).ok_or_else(|| {
+            warn!("can't convert image of format {:?} to Y800", image.format());
+            ZBarErrorType::Complex(ZBarError::ZBAR_ERR_UNSUPPORTED)
+        })?;
+        self.scan_image_copy(image, &converted)
+    }
+    fn scan_image_copy<T, U>(
+        &self,
+        image: &ZBarImage<T>,
+        copy: &ZBarImage<U>
+    ) -> ZBarResult<ZBarSymbolSet>
+    {
+        let symbols = self.scan_image(copy)?;
+        unsafe { ffi::zbar_image_set_symbols(image.image(), symbols.symbol_set()) };
+        // symbols can be unwrapped because they have just been set
+        Ok(image.symbols().unwrap())
+    }
+    /// Scans the image like `scan_image` and additionally returns the time the scan took.
+    ///
+    /// This is meant for benchmarking, e.g. to compare the impact of different density
+    /// configurations.
+    pub fn scan_image_timed<T>(
+        &self,
+        image: &ZBarImage<T>
+    ) -> ZBarResult<(ZBarSymbolSet, Duration)>
+    {
+        let start = Instant::now();
+        self.scan_image(image).map(|symbols| (symbols, start.elapsed()))
+    }
+    /// Scans the image as well as its rotations by 90°, 180° and 270°.
+    ///
+    /// Symbols found in more than one orientation are only returned once. This improves the
+    /// recall for rotated 1D barcodes at the cost of four scans. Images that are not 8-bit
+    /// grayscale are converted to `Y800` first. Returns `ZBAR_ERR_UNSUPPORTED` if ZBar can't
+    /// convert them.
+    pub fn scan_all_orientations<T>(&self, image: &ZBarImage<T>) -> ZBarResult<Vec<OwnedSymbol>> {
+        if !image.format().is_grayscale() {
+            return match image.convert(Y800) {
+                Some(image) => self.scan_all_orientations(&image),
+                None        => Err(ZBarErrorType::Complex(ZBarError::ZBAR_ERR_UNSUPPORTED)),
+            };
+        }
+        let rotated_90 = image.rotated_90();
+        let rotated_180 = rotated_90.rotated_90();
+        let rotated_270 = rotated_180.rotated_90();
+        Ok(collect_unique(vec![
+            self.scan_image(image)?,
+            self.scan_image(&rotated_90)?,
+            self.scan_image(&rotated_180)?,
+            self.scan_image(&rotated_270)?,
+        ]))
+    }
+    /// Scans raw grayscale (`Y800`) pixel data without requiring a `ZBarImage`.
+    ///
+    /// Returns an error if `data` does not hold exactly `width * height` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    ///
+    /// let scanner = ZBarImageScanner::builder().build().unwrap();
+    /// let symbol_set = scanner.scan_y800(2, 2, vec![0; 2 * 2]).unwrap();
+    /// assert!(symbol_set.first_symbol().is_none());
+    /// ```
+    pub fn scan_y800(
+        &self,
+        width: u32,
+        height: u32,
+        data: impl AsRef<[u8]>
+    ) -> ZBarResult<ZBarSymbolSet>
+    {
+        ZBarImage::new(width, height, Y800, data)
+            .map_err(|_| ZBarErrorType::Complex(ZBarError::ZBAR_ERR_INVALID))
+            .and_then(|image| self.scan_image(&image))
+    }
+    /// Scans packed YUV 4:2:2 (`YUYV`) pixel data, e.g. as captured by webcams.
+    ///
+    /// The data is converted to `Y800` by ZBar before scanning. Returns an error if `data` does
+    /// not hold exactly `width * height * 2` bytes.
+    pub fn scan_yuyv(
+        &self,
+        width: u32,
+        height: u32,
+        data: impl AsRef<[u8]>
+    ) -> ZBarResult<ZBarSymbolSet>
+    {
+        self.scan_converted(width, height, YUYV, data)
+    }
+    /// Scans planar YUV 4:2:0 (`NV12`) pixel data, e.g. as captured by webcams.
+    ///
+    /// The data is converted to `Y800` by ZBar before scanning. Returns an error if `data` does
+    /// not hold a full luma plane followed by the interleaved chroma plane.
+    pub fn scan_nv12(
+        &self,
+        width: u32,
+        height: u32,
+        data: impl AsRef<[u8]>
+    ) -> ZBarResult<ZBarSymbolSet>
+    {
+        self.scan_converted(width, height, NV12, data)
+    }
+    fn scan_converted(
+        &self,
+        width: u32,
+        height: u32,
+        format: Format,
+        data: impl AsRef<[u8]>
+    ) -> ZBarResult<ZBarSymbolSet>
+    {
+        let image = ZBarImage::new(width, height, format, data)
+            .map_err(|_| ZBarErrorType::Complex(ZBarError::ZBAR_ERR_INVALID))?;
+        image
+            .convert(Y800)
+            .ok_or(ZBarErrorType::Complex(ZBarError::ZBAR_ERR_UNSUPPORTED))
+            .and_then(|image| self.scan_image(&image))
+    }
+    /// Scans only the given region of the image.
+    ///
+    /// On the fork build the region is applied as crop, which is restored after scanning. On
+    /// plain ZBar the region is copied into a scratch `Y800` image, so the locations of the
+    /// returned symbols are relative to the region in that case. Either way, padded rows (see
+    /// `ZBarImage::set_stride`) are skipped and other formats are converted to `Y800` like in
+    /// `scan_image`. Returns an error if the region exceeds the image.
+    pub fn scan_roi<T>(
+        &self,
+        image: &ZBarImage<T>,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32
+    ) -> ZBarResult<ZBarSymbolSet>
+    {
+        let exceeds = |offset: u32, len: u32, max: u32| {
+            offset.checked_add(len).map_or(true, |end| end > max)
+        };
+        if exceeds(x, width, image.width()) || exceeds(y, height, image.height()) {
+            return Err(ZBarErrorType::Complex(ZBarError::ZBAR_ERR_INVALID));
+        }
+        self.scan_region(image, x, y, width, height)
+    }
+    #[cfg(feature = "zbar_fork")]
+    fn scan_region<T>(
+        &self,
+        image: &ZBarImage<T>,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32
+    ) -> ZBarResult<ZBarSymbolSet>
+    {
+        let crop = image.crop();
+        image.set_crop(x, y, width, height);
+        let symbols = self.scan_image(image);
+        image.set_crop(crop.0, crop.1, crop.2, crop.3);
+        symbols
+    }
+    #[cfg(not(feature = "zbar_fork"))]
+    fn scan_region<T>(
+        &self,
+        image: &ZBarImage<T>,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32
+    ) -> ZBarResult<ZBarSymbolSet>
+    {
+        if !image.format().is_grayscale() {
+            let converted = image
+                .convert(Y800)
+                .ok_or(ZBarErrorType::Complex(ZBarError::ZBAR_ERR_UNSUPPORTED))?;
+            return self.scan_region(&converted, x, y, width, height);
+        }
+        let (x, width) = (x as usize, width as usize);
+        let region = image
+            .rows()
+            .skip(y as usize)
+            .take(height as usize)
+            .flat_map(|row| row[x..x + width].iter().cloned())
+            .collect();
+        self.scan_y800(width as u32, height, region)
+    }
+    /// Scans the image and returns only the symbols that have been newly verified by the cache.
+    ///
+    /// Only useful with caching enabled: symbols that have already been reported by a previous
+    /// scan (`count() > 0`) and symbols that are still uncertain (`count() < 0`) are left out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    ///
+    /// let image = ZBarImage::new(1, 1, Format::from_label("Y8"), vec![1]).unwrap();
+    /// let scanner = ZBarImageScanner::builder().with_cache(true).build().unwrap();
+    /// for symbol in scanner.scan_new_only(&image).unwrap() {
+    ///     println!("new symbol: {}", symbol.data());
+    /// }
+    /// ```
+    pub fn scan_new_only<T>(&self, image: &ZBarImage<T>) -> ZBarResult<Vec<ZBarSymbol>> {
+        self.scan_image(image)
+            .map(|symbols| symbols.iter().filter(|symbol| symbol.count() == 0).collect())
+    }
 }
 
 unsafe impl Send for ZBarImageScanner {}
 
 impl Default for ZBarImageScanner {
     fn default() -> Self {
-        let scanner = ZBarImageScanner { scanner: unsafe { ffi::zbar_image_scanner_create() } };
+        let scanner = ZBarImageScanner {
+            scanner: unsafe { ffi::zbar_image_scanner_create() },
+            text_policy: Cell::new(TextPolicy::default()),
+            config: RefCell::new(HashMap::new()),
+            cache: Cell::new(false),
+        };
         // safe to unwrap here
         scanner.set_config(ZBarSymbolType::ZBAR_NONE, ZBarConfig::ZBAR_CFG_ENABLE, 0).unwrap();
         scanner
     }
 }
+impl fmt::Debug for ZBarImageScanner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ZBarImageScanner").field("scanner", &self.scanner).finish()
+    }
+}
 impl Drop for ZBarImageScanner {
     fn drop(&mut self) { unsafe { ffi::zbar_image_scanner_destroy(self.scanner) } }
 }
 
+/// Scans consecutive frames of the same size reusing a single image buffer.
+///
+/// Every frame is copied into the buffer of the same `ZBarImage`, so no image has to be
+/// allocated per frame.
+///
+/// # Examples
+///
+/// ```
+/// use zbars::image_scanner::FrameScanner;
+/// use zbars::prelude::*;
+///
+/// let scanner = ZBarImageScanner::builder()
+///     .with_config(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+///     .build()
+///     .unwrap();
+/// let mut frame_scanner = FrameScanner::new(scanner, 2, 2).unwrap();
+/// for frame in vec![vec![0; 2 * 2], vec![255; 2 * 2]] {
+///     let symbols = frame_scanner.scan_frame(&frame).unwrap();
+///     println!("{} symbols", symbols.size());
+/// }
+/// ```
+pub struct FrameScanner {
+    scanner: ZBarImageScanner,
+    image: ZBarImage<Vec<u8>>,
+}
+impl FrameScanner {
+    /// Creates a `FrameScanner` for `Y800` frames of the given dimensions.
+    ///
+    /// Returns an error if `width` or `height` is zero or the frame size overflows.
+    pub fn new(
+        scanner: ZBarImageScanner,
+        width: u32,
+        height: u32
+    ) -> Result<Self, ZBarImageError>
+    {
+        let len = Y800
+            .frame_len(width, height)
+            .ok_or(ZBarImageError::DimensionOverflow(width, height))?;
+        Ok(FrameScanner {
+            scanner,
+            image: ZBarImage::new(width, height, Y800, vec![0; len as usize])?,
+        })
+    }
+    pub fn scanner(&self) -> &ZBarImageScanner { &self.scanner }
+    /// Copies `new_data` into the image buffer and scans it.
+    ///
+    /// Returns an error if the length of `new_data` doesn't match the frame dimensions.
+    pub fn scan_frame(&mut self, new_data: &[u8]) -> ZBarResult<ZBarSymbolSet> {
+        match self.image.data_mut() {
+            Some(data) if data.len() == new_data.len() => data.copy_from_slice(new_data),
+            _ => return Err(ZBarErrorType::Complex(ZBarError::ZBAR_ERR_INVALID)),
+        }
+        self.scanner.scan_image(&self.image)
+    }
+}
+
 #[derive(Default)]
 pub struct ImageScannerBuilder {
     cache: bool,
+    text_policy: TextPolicy,
     config: Vec<(ZBarSymbolType, ZBarConfig, i32)>,
 }
 impl ImageScannerBuilder {
     pub fn new() -> Self {
-        Self { cache: false, config: vec![], }
+        Self { cache: false, text_policy: TextPolicy::default(), config: vec![], }
     }
     pub fn with_config(
         &mut self,
@@ -78,9 +708,71 @@ impl ImageScannerBuilder {
         self.config.push((symbol_type, config, value)); self
     }
     pub fn with_cache(&mut self, cache: bool) -> &mut Self { self.cache = cache; self }
+    pub fn with_length_range(
+        &mut self,
+        symbol_type: ZBarSymbolType,
+        min: i32,
+        max: i32
+    ) -> &mut Self
+    {
+        self.with_config(symbol_type, ZBarConfig::ZBAR_CFG_MIN_LEN, min)
+            .with_config(symbol_type, ZBarConfig::ZBAR_CFG_MAX_LEN, max)
+    }
+    pub fn with_text_policy(&mut self, text_policy: TextPolicy) -> &mut Self {
+        self.text_policy = text_policy; self
+    }
+    /// Adds config strings like `qrcode.enable=1`, each parsed with `parse_config`.
+    ///
+    /// Nothing is added if any of the strings can't be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::image_scanner::ImageScannerBuilder;
+    ///
+    /// let scanner = ImageScannerBuilder::new()
+    ///     .with_config_strings(&["qrcode.enable=1", "code128.enable=1"])
+    ///     .unwrap()
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn with_config_strings(&mut self, configs: &[&str]) -> ZBarResult<&mut Self> {
+        let configs = configs
+            .iter()
+            .map(parse_config)
+            .collect::<ZBarResult<Vec<_>>>()?;
+        self.config.extend(configs);
+        Ok(self)
+    }
+    /// Enables EAN-13, EAN-8, UPC-A, UPC-E and CODE-128.
+    pub fn with_retail_symbologies(&mut self) -> &mut Self {
+        RETAIL_SYMBOL_TYPES.iter().for_each(|symbol_type| {
+            self.with_config(*symbol_type, ZBarConfig::ZBAR_CFG_ENABLE, 1);
+        });
+        self
+    }
 
+    /// Builds the `ZBarImageScanner` applying all configs in the order they have been added.
+    ///
+    /// Returns `ZBAR_ERR_INVALID` if the same config has been added more than once for a
+    /// symbology with conflicting values, e.g. enabling and disabling CODE-128.
     pub fn build(&self) -> ZBarResult<ZBarImageScanner> {
+        let mut values = HashMap::new();
+        for &(symbol_type, config, value) in &self.config {
+            match values.insert((symbol_type, config), value) {
+                Some(previous) if previous != value => {
+                    warn!(
+                        "conflicting values {} and {} for {:?} of {:?}",
+                        previous, value, config, symbol_type
+                    );
+                    return Err(ZBarErrorType::Complex(ZBarError::ZBAR_ERR_INVALID));
+                }
+                _ => {}
+            }
+        }
+
         let scanner = ZBarImageScanner::new();
+        scanner.set_text_policy(self.text_policy);
 
         self.config
             .iter()
@@ -92,6 +784,89 @@ impl ImageScannerBuilder {
     }
 }
 
+/// A bounded pool of identically configured `ZBarImageScanner`s.
+///
+/// Creating and configuring a scanner for every request is comparatively expensive. The pool
+/// creates its scanners once and lends them out for a single scan. It is `Sync`, so it can be
+/// shared between threads, e.g. in an `Arc`.
+///
+/// # Examples
+///
+/// ```
+/// use zbars::prelude::*;
+/// use zbars::image_scanner::ScannerPool;
+///
+/// let pool = ScannerPool::new(
+///     4,
+///     &[(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1)]
+/// ).unwrap();
+/// let image = ZBarImage::new(1, 1, Y800, vec![0]).unwrap();
+/// assert!(pool.scan(&image).unwrap().first_symbol().is_none());
+/// ```
+pub struct ScannerPool {
+    scanners: Mutex<Vec<ZBarImageScanner>>,
+    available: Condvar,
+}
+impl ScannerPool {
+    /// Creates a pool of `size` scanners, each built with the given configs.
+    ///
+    /// Returns `ZBAR_ERR_INVALID` if `size` is `0`.
+    pub fn new(size: usize, configs: &[(ZBarSymbolType, ZBarConfig, i32)]) -> ZBarResult<Self> {
+        if size == 0 {
+            return Err(ZBarErrorType::Complex(ZBarError::ZBAR_ERR_INVALID));
+        }
+        let mut builder = ImageScannerBuilder::new();
+        configs.iter().for_each(|&(symbol_type, config, value)| {
+            builder.with_config(symbol_type, config, value);
+        });
+        let scanners = (0..size)
+            .map(|_| builder.build())
+            .collect::<ZBarResult<Vec<_>>>()?;
+        Ok(Self { scanners: Mutex::new(scanners), available: Condvar::new() })
+    }
+    /// Scans the image with a scanner of the pool.
+    ///
+    /// Blocks until a scanner is available if all of them are in use. The scanner is returned
+    /// to the pool even if scanning panics.
+    pub fn scan<T>(&self, image: &ZBarImage<T>) -> ZBarResult<ZBarSymbolSet> {
+        self.checkout().scan_image(image)
+    }
+    fn checkout(&self) -> PooledScanner {
+        let mut scanners = self.scanners();
+        while scanners.is_empty() {
+            scanners = self.available.wait(scanners).unwrap_or_else(PoisonError::into_inner);
+        }
+        PooledScanner { pool: self, scanner: scanners.pop() }
+    }
+    /// Locks the idle scanners.
+    ///
+    /// Scanners are only pushed and popped while the lock is held, so the list stays consistent
+    /// even if another thread panicked meanwhile and the poisoning can be ignored.
+    fn scanners(&self) -> MutexGuard<Vec<ZBarImageScanner>> {
+        self.scanners.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A scanner checked out of a `ScannerPool`, which is returned to the pool on drop.
+struct PooledScanner<'a> {
+    pool: &'a ScannerPool,
+    // only taken on drop
+    scanner: Option<ZBarImageScanner>,
+}
+impl<'a> Deref for PooledScanner<'a> {
+    type Target = ZBarImageScanner;
+
+    fn deref(&self) -> &ZBarImageScanner { self.scanner.as_ref().unwrap() }
+}
+impl<'a> Drop for PooledScanner<'a> {
+    fn drop(&mut self) {
+        if let Some(scanner) = self.scanner.take() {
+            self.pool.scanners().push(scanner);
+            self.pool.available.notify_one();
+        }
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "from_image")]
 mod test {
@@ -117,6 +892,207 @@ mod test {
         assert!(iter.next().is_none());
     }
 
+    #[test]
+    fn test_scan_paths() {
+        let results = scan_paths(
+            &["test/qr_hello-world.png", "test/missing.png", "test/code128.gif"],
+            &[
+                (ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1),
+                (ZBarSymbolType::ZBAR_CODE128, ZBarConfig::ZBAR_CFG_ENABLE, 1),
+            ]
+        );
+        assert_eq!(results.len(), 3);
+
+        assert_eq!(results[0].0, PathBuf::from("test/qr_hello-world.png"));
+        assert_eq!(results[0].1.as_ref().unwrap()[0].data(), "Hello World");
+
+        assert_eq!(results[1].0, PathBuf::from("test/missing.png"));
+        match results[1].1 {
+            Err(ZBarErrorType::Complex(ZBarError::ZBAR_ERR_SYSTEM)) => {},
+            ref other => panic!("unexpected result: {:?}", other),
+        }
+
+        assert_eq!(results[2].1.as_ref().unwrap()[0].data(), "Screwdriver");
+    }
+
+    #[test]
+    fn test_scan_yuyv() {
+        let luma = ZBarImage::from_path("test/code128.gif").unwrap();
+        let yuyv = luma.data().iter().flat_map(|y| vec![*y, 128]).collect::<Vec<_>>();
+
+        let scanner = ZBarImageScanner::new();
+        scanner.enable(ZBarSymbolType::ZBAR_CODE128).unwrap();
+        let symbols = scanner.scan_yuyv(luma.width(), luma.height(), yuyv).unwrap();
+
+        assert_code128(symbols.first_symbol().unwrap());
+    }
+
+    #[test]
+    fn test_scan_nv12() {
+        let luma = ZBarImage::from_path("test/code128.gif").unwrap();
+        let mut nv12 = luma.data().to_vec();
+        nv12.extend(vec![128; luma.data().len() / 2]);
+
+        let scanner = ZBarImageScanner::new();
+        scanner.enable(ZBarSymbolType::ZBAR_CODE128).unwrap();
+        let symbols = scanner.scan_nv12(luma.width(), luma.height(), nv12).unwrap();
+
+        assert_code128(symbols.first_symbol().unwrap());
+    }
+
+    #[test]
+    fn test_scan_image_converts_format() {
+        let luma = ZBarImage::from_path("test/code128.gif").unwrap();
+        let yuyv = luma.data().iter().flat_map(|y| vec![*y, 128]).collect::<Vec<_>>();
+        let image = ZBarImage::new(luma.width(), luma.height(), YUYV, yuyv).unwrap();
+
+        let scanner = ZBarImageScanner::new();
+        scanner.enable(ZBarSymbolType::ZBAR_CODE128).unwrap();
+        assert_code128(scanner.scan_image(&image).unwrap().first_symbol().unwrap());
+        assert_code128(image.first_symbol().unwrap());
+    }
+
+    #[test]
+    fn test_scan_image_strided() {
+        let luma = ZBarImage::from_path("test/code128.gif").unwrap();
+        // pad every row with 3 black pixels that would break the bars if they weren't skipped
+        let padded = luma
+            .rows()
+            .flat_map(|row| row.iter().cloned().chain(vec![0; 3]))
+            .collect::<Vec<_>>();
+        let image = ZBarImage::new_strided(
+            luma.width(), luma.height(), luma.width() + 3, padded
+        ).unwrap();
+
+        let scanner = ZBarImageScanner::new();
+        scanner.enable(ZBarSymbolType::ZBAR_CODE128).unwrap();
+        assert_code128(scanner.scan_image(&image).unwrap().first_symbol().unwrap());
+        assert_code128(image.first_symbol().unwrap());
+    }
+
+    #[test]
+    fn test_scan_image_converts_rgb() {
+        let luma = ZBarImage::from_path("test/code128.gif").unwrap();
+        let rgb = luma.data().iter().flat_map(|y| vec![*y; 3]).collect::<Vec<_>>();
+        let image = ZBarImage::new(luma.width(), luma.height(), Format::from_label("RGB3"), rgb)
+            .unwrap();
+
+        let scanner = ZBarImageScanner::new();
+        scanner.enable(ZBarSymbolType::ZBAR_CODE128).unwrap();
+        assert_code128(scanner.scan_image(&image).unwrap().first_symbol().unwrap());
+    }
+
+    #[test]
+    fn test_scan_image_unknown_format() {
+        // only 1 byte per pixel could be checked, so ZBar must not read it as another format
+        let image = ZBarImage::new(2, 2, Format::from_label("ABCD"), vec![0; 2 * 2]).unwrap();
+        match ZBarImageScanner::new().scan_image(&image) {
+            Err(ZBarErrorType::Complex(ZBarError::ZBAR_ERR_UNSUPPORTED)) => {},
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(ZBarImage::new(2, 2, Format::from_label("RGB3"), vec![0; 2 * 2]).is_err());
+    }
+
+    #[test]
+    fn test_scan_yuyv_invalid_len() {
+        assert!(ZBarImageScanner::new().scan_yuyv(2, 2, vec![0; 2 * 2]).is_err());
+    }
+
+    #[test]
+    fn test_builder_conflicting_config() {
+        let result = ImageScannerBuilder::new()
+            .with_config(ZBarSymbolType::ZBAR_CODE128, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .with_config(ZBarSymbolType::ZBAR_CODE128, ZBarConfig::ZBAR_CFG_ENABLE, 0)
+            .build();
+        match result {
+            Err(ZBarErrorType::Complex(ZBarError::ZBAR_ERR_INVALID)) => {},
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        assert!(ImageScannerBuilder::new()
+            .with_config(ZBarSymbolType::ZBAR_CODE128, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .with_config(ZBarSymbolType::ZBAR_CODE128, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_scanner_pool() {
+        use std::{
+            sync::Arc,
+            thread
+        };
+
+        let pool = Arc::new(ScannerPool::new(
+            2,
+            &[(ZBarSymbolType::ZBAR_CODE128, ZBarConfig::ZBAR_CFG_ENABLE, 1)]
+        ).unwrap());
+        let handles = (0..8)
+            .map(|_| {
+                let pool = pool.clone();
+                thread::spawn(move || {
+                    let image = ZBarImage::from_path("test/code128.gif").unwrap();
+                    pool.scan(&image).unwrap().data_strings()
+                })
+            })
+            .collect::<Vec<_>>();
+        handles.into_iter().for_each(|handle| {
+            assert_eq!(handle.join().unwrap(), vec!["Screwdriver"]);
+        });
+
+        assert!(ScannerPool::new(0, &[]).is_err());
+    }
+
+    #[test]
+    fn test_scanner_pool_panic() {
+        use std::panic::{
+            self,
+            AssertUnwindSafe
+        };
+
+        let pool = ScannerPool::new(
+            1,
+            &[(ZBarSymbolType::ZBAR_CODE128, ZBarConfig::ZBAR_CFG_ENABLE, 1)]
+        ).unwrap();
+        let image = ZBarImage::from_path("test/code128.gif").unwrap();
+
+        // a panic while scanning returns the only scanner
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| {
+            let _scanner = pool.checkout();
+            panic!("scan failed");
+        })).is_err());
+        assert_eq!(pool.scan(&image).unwrap().data_strings(), vec!["Screwdriver"]);
+
+        // a panic while holding the lock poisons it
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| {
+            let _scanners = pool.scanners.lock().unwrap();
+            panic!("poisoned");
+        })).is_err());
+        assert!(pool.scanners.is_poisoned());
+        assert_eq!(pool.scan(&image).unwrap().data_strings(), vec!["Screwdriver"]);
+    }
+
+    #[test]
+    fn test_builder_config_strings() {
+        let scanner = ImageScannerBuilder::new()
+            .with_config_strings(&["qrcode.enable=1", "code128.enable=1"])
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+        scanner.scan_image(&image).unwrap();
+        assert_qrcode(image.first_symbol().unwrap());
+
+        let image = ZBarImage::from_path("test/code128.gif").unwrap();
+        scanner.scan_image(&image).unwrap();
+        assert_code128(image.first_symbol().unwrap());
+
+        assert!(ImageScannerBuilder::new()
+            .with_config_strings(&["qrcode.enable=1", "Not valid"])
+            .is_err());
+    }
+
     #[test]
     fn test_qrcode_disabled() {
         let image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
@@ -168,12 +1144,392 @@ mod test {
         assert!(image.first_symbol().is_none());
     }
 
+    #[test]
+    fn test_scan_image_timed() {
+        let image = ZBarImage::from_path("test/code128.gif").unwrap();
+
+        let scanner = ImageScannerBuilder::new()
+            .with_config(ZBarSymbolType::ZBAR_CODE128, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .build()
+            .unwrap();
+        let (symbols, duration) = scanner.scan_image_timed(&image).unwrap();
+
+        assert!(duration > Duration::from_secs(0));
+        assert_code128(symbols.first_symbol().unwrap());
+    }
+
+    #[test]
+    fn test_scan_all_orientations() {
+        let image = ZBarImage::from_path("test/code128.gif").unwrap().rotated_90();
+
+        let scanner = ImageScannerBuilder::new()
+            .with_config(ZBarSymbolType::ZBAR_CODE128, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .build()
+            .unwrap();
+        let symbols = scanner.scan_all_orientations(&image).unwrap();
+
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].symbol_type(), ZBarSymbolType::ZBAR_CODE128);
+        assert_eq!(symbols[0].data(), "Screwdriver");
+    }
+
+    #[test]
+    fn test_scan_all_orientations_yuyv() {
+        let luma = ZBarImage::from_path("test/code128.gif").unwrap().rotated_90();
+        let yuyv = luma.data().iter().flat_map(|y| vec![*y, 128]).collect::<Vec<_>>();
+        let image = ZBarImage::new(luma.width(), luma.height(), YUYV, yuyv).unwrap();
+
+        let scanner = ZBarImageScanner::new();
+        scanner.enable(ZBarSymbolType::ZBAR_CODE128).unwrap();
+        let symbols = scanner.scan_all_orientations(&image).unwrap();
+
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].data(), "Screwdriver");
+    }
+
     fn assert_code128(symbol: ZBarSymbol) {
         assert_eq!(symbol.symbol_type(), ZBarSymbolType::ZBAR_CODE128);
         assert_eq!(symbol.data(), "Screwdriver");
         assert_eq!(symbol.next().is_none(), true);
     }
 
+    #[test]
+    fn test_retail_scanner() {
+        let image = ZBarImage::from_path("test/code128.gif").unwrap();
+
+        let scanner = retail_scanner().unwrap();
+        scanner.scan_image(&image).unwrap();
+
+        assert_code128(image.first_symbol().unwrap());
+    }
+
+    #[test]
+    fn test_scan_y800() {
+        let image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+
+        let scanner = ImageScannerBuilder::new()
+            .with_config(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .build()
+            .unwrap();
+        let symbols = scanner
+            .scan_y800(image.width(), image.height(), image.data().to_vec())
+            .unwrap();
+
+        assert_qrcode(symbols.first_symbol().unwrap());
+    }
+
+    #[test]
+    fn test_scan_y800_len_mismatch() {
+        let scanner = ImageScannerBuilder::new().build().unwrap();
+        assert!(scanner.scan_y800(2, 3, vec![0; 5]).is_err());
+    }
+
+    #[test]
+    fn test_emit_check() {
+        let scanner = ImageScannerBuilder::new()
+            .with_config(ZBarSymbolType::ZBAR_EAN13, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .build()
+            .unwrap();
+
+        let image = ZBarImage::from_path("test/ean13.png").unwrap();
+        scanner.set_emit_check(ZBarSymbolType::ZBAR_EAN13, true).unwrap();
+        assert_eq!(
+            scanner.scan_image(&image).unwrap().first_symbol().unwrap().data(),
+            "4006381333931"
+        );
+
+        let image = ZBarImage::from_path("test/ean13.png").unwrap();
+        scanner.set_emit_check(ZBarSymbolType::ZBAR_EAN13, false).unwrap();
+        assert_eq!(
+            scanner.scan_image(&image).unwrap().first_symbol().unwrap().data(),
+            "400638133393"
+        );
+    }
+
+    #[test]
+    fn test_text_policy() {
+        let image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+
+        let scanner = ImageScannerBuilder::new()
+            .with_config(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .with_text_policy(TextPolicy::Bytes)
+            .build()
+            .unwrap();
+        let symbol = scanner.scan_image(&image).unwrap().first_symbol().unwrap();
+
+        assert_eq!(scanner.text_policy(), TextPolicy::Bytes);
+        assert_eq!(scanner.symbol_data(&symbol).unwrap(), SymbolData::Bytes(b"Hello World"));
+    }
+
+    #[test]
+    fn test_text_policy_invalid_utf8() {
+        let image = ZBarImage::from_path("test/code128.gif").unwrap();
+        let scanner = ZBarImageScanner::new();
+        scanner.enable(ZBarSymbolType::ZBAR_CODE128).unwrap();
+        let symbols = scanner.scan_image(&image).unwrap();
+        // ZBar's decoders convert their output to text, so patch the decoded data in place to
+        // get a symbol with invalid UTF-8
+        unsafe {
+            let symbol = ffi::zbar_symbol_set_first_symbol(symbols.symbol_set());
+            *(ffi::zbar_symbol_get_data(symbol) as *mut u8) = 0xff;
+        }
+        let symbol = symbols.first_symbol().unwrap();
+
+        assert!(scanner.symbol_data(&symbol).is_err());
+
+        scanner.set_text_policy(TextPolicy::Lossy);
+        assert_eq!(
+            scanner.symbol_data(&symbol).unwrap(),
+            SymbolData::Text("\u{fffd}crewdriver".into())
+        );
+
+        scanner.set_text_policy(TextPolicy::Bytes);
+        assert_eq!(scanner.symbol_data(&symbol).unwrap(), SymbolData::Bytes(b"\xffcrewdriver"));
+
+        scanner.set_text_policy(TextPolicy::Strict);
+        assert!(scanner.symbol_data(&symbol).is_err());
+    }
+
+    #[test]
+    fn test_debug() {
+        assert!(format!("{:?}", ZBarImageScanner::new()).starts_with("ZBarImageScanner"));
+    }
+
+    #[test]
+    fn test_frame_scanner() {
+        let hello = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+        let hallo = ZBarImage::from_path("test/qr_hallo-welt.png").unwrap();
+
+        let scanner = ImageScannerBuilder::new()
+            .with_config(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .build()
+            .unwrap();
+        let mut frame_scanner =
+            FrameScanner::new(scanner, hello.width(), hello.height()).unwrap();
+
+        assert_eq!(
+            frame_scanner.scan_frame(hello.data()).unwrap().first_symbol().unwrap().data(),
+            "Hello World"
+        );
+        assert_eq!(
+            frame_scanner.scan_frame(hallo.data()).unwrap().first_symbol().unwrap().data(),
+            "Hallo Welt"
+        );
+    }
+
+    #[test]
+    fn test_frame_scanner_len_mismatch() {
+        let mut frame_scanner = FrameScanner::new(ZBarImageScanner::new(), 2, 3).unwrap();
+        assert!(frame_scanner.scan_frame(&[0; 5]).is_err());
+    }
+
+    #[test]
+    fn test_frame_scanner_invalid_dimensions() {
+        match FrameScanner::new(ZBarImageScanner::new(), 0, 3) {
+            Err(ZBarImageError::ZeroDimension(0, 3)) => {},
+            Err(other) => panic!("unexpected error: {}", other),
+            Ok(_)      => panic!("unexpected success"),
+        }
+        match FrameScanner::new(ZBarImageScanner::new(), u32::max_value(), 2) {
+            Err(ZBarImageError::DimensionOverflow(_, 2)) => {},
+            Err(other) => panic!("unexpected error: {}", other),
+            Ok(_)      => panic!("unexpected success"),
+        }
+    }
+
+    #[test]
+    fn test_enable_disable() {
+        let scanner = ZBarImageScanner::new();
+
+        let image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+        scanner.enable(ZBarSymbolType::ZBAR_QRCODE).unwrap();
+        scanner.scan_image(&image).unwrap();
+        assert_qrcode(image.first_symbol().unwrap());
+
+        let image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+        scanner.disable(ZBarSymbolType::ZBAR_QRCODE).unwrap();
+        scanner.scan_image(&image).unwrap();
+        assert!(image.first_symbol().is_none());
+    }
+
+    #[test]
+    fn test_set_config_typed() {
+        let image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+
+        let scanner = ZBarImageScanner::new();
+        scanner.set_config_typed(ZBarSymbolType::ZBAR_QRCODE, Config::Enable(true)).unwrap();
+        scanner.scan_image(&image).unwrap();
+
+        assert_qrcode(image.first_symbol().unwrap());
+    }
+
+    #[test]
+    fn test_config_value() {
+        let scanner = ZBarImageScanner::new();
+        assert_eq!(
+            scanner.config_value(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE),
+            Some(0)
+        );
+        assert!(scanner
+            .config_value(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_MIN_LEN)
+            .is_none());
+
+        scanner.set_config(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1).unwrap();
+        assert_eq!(
+            scanner.config_value(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_set_config_shared() {
+        fn enable_qrcode(scanner: &ZBarImageScanner) {
+            scanner.set_config(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+                .unwrap();
+        }
+
+        let image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+        let scanner = ZBarImageScanner::new();
+        let shared = &scanner;
+        assert!(shared.scan_image(&image).unwrap().first_symbol().is_none());
+
+        enable_qrcode(shared);
+        assert_qrcode(shared.scan_image(&image).unwrap().first_symbol().unwrap());
+    }
+
+    #[test]
+    fn test_enabled_symbologies() {
+        assert!(ZBarImageScanner::new().enabled_symbologies().is_empty());
+
+        let scanner = ImageScannerBuilder::new()
+            .with_config(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .with_config(ZBarSymbolType::ZBAR_CODE128, ZBarConfig::ZBAR_CFG_ENABLE, 0)
+            .build()
+            .unwrap();
+        let enabled = scanner.enabled_symbologies();
+        assert!(enabled.contains(&ZBarSymbolType::ZBAR_QRCODE));
+        assert!(!enabled.contains(&ZBarSymbolType::ZBAR_CODE128));
+    }
+
+    #[test]
+    fn test_length_range() {
+        let image = ZBarImage::from_path("test/code128.gif").unwrap();
+
+        let scanner = ImageScannerBuilder::new()
+            .with_config(ZBarSymbolType::ZBAR_CODE128, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .with_length_range(ZBarSymbolType::ZBAR_CODE128, 1, 20)
+            .build()
+            .unwrap();
+        scanner.scan_image(&image).unwrap();
+        assert_code128(image.first_symbol().unwrap());
+
+        // "Screwdriver" has 11 characters
+        scanner.set_length_range(ZBarSymbolType::ZBAR_CODE128, 12, 20).unwrap();
+        scanner.scan_image(&image).unwrap();
+        assert!(image.first_symbol().is_none());
+    }
+
+    #[test]
+    fn test_apply_config_file() {
+        let path = ::std::env::temp_dir().join("zbars_apply_config_file.conf");
+        fs::write(&path, "# enable QR codes\n\nqrcode.enable=1\n").unwrap();
+
+        let image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+        let scanner = ZBarImageScanner::new();
+        scanner.apply_config_file(&path).unwrap();
+        scanner.scan_image(&image).unwrap();
+
+        assert_qrcode(image.first_symbol().unwrap());
+    }
+
+    #[test]
+    fn test_apply_config_file_missing() {
+        assert!(ZBarImageScanner::new().apply_config_file("/nowhere/nothing").is_err());
+    }
+
+    #[test]
+    fn test_scan_roi() {
+        let image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+
+        let scanner = ImageScannerBuilder::new()
+            .with_config(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .build()
+            .unwrap();
+
+        let symbols = scanner.scan_roi(&image, 1, 1, 148, 148).unwrap();
+        assert_eq!(symbols.first_symbol().unwrap().data(), "Hello World");
+
+        let symbols = scanner.scan_roi(&image, 0, 0, 75, 150).unwrap();
+        assert!(symbols.first_symbol().is_none());
+    }
+
+    #[test]
+    fn test_scan_roi_strided() {
+        let luma = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+        // black padding, which would show up inside the region if it weren't skipped
+        let padded = luma
+            .rows()
+            .flat_map(|row| row.iter().cloned().chain(vec![0; 5]))
+            .collect::<Vec<_>>();
+        let image = ZBarImage::new_strided(
+            luma.width(), luma.height(), luma.width() + 5, padded
+        ).unwrap();
+
+        let scanner = ZBarImageScanner::new();
+        scanner.enable(ZBarSymbolType::ZBAR_QRCODE).unwrap();
+
+        let symbols = scanner.scan_roi(&image, 1, 1, 148, 148).unwrap();
+        assert_eq!(symbols.first_symbol().unwrap().data(), "Hello World");
+        let symbols = scanner.scan_roi(&image, 0, 0, 75, 150).unwrap();
+        assert!(symbols.first_symbol().is_none());
+    }
+
+    #[test]
+    fn test_scan_roi_yuyv() {
+        let luma = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+        let yuyv = luma.data().iter().flat_map(|y| vec![*y, 128]).collect::<Vec<_>>();
+        let image = ZBarImage::new(luma.width(), luma.height(), YUYV, yuyv).unwrap();
+
+        let scanner = ZBarImageScanner::new();
+        scanner.enable(ZBarSymbolType::ZBAR_QRCODE).unwrap();
+
+        let symbols = scanner.scan_roi(&image, 1, 1, 148, 148).unwrap();
+        assert_eq!(symbols.first_symbol().unwrap().data(), "Hello World");
+        let symbols = scanner.scan_roi(&image, 0, 0, 75, 150).unwrap();
+        assert!(symbols.first_symbol().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "zbar_fork")]
+    fn test_scan_roi_restores_crop() {
+        let image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+        ZBarImageScanner::new().scan_roi(&image, 1, 1, 148, 148).unwrap();
+        assert_eq!(image.crop(), (0, 0, 150, 150));
+    }
+
+    #[test]
+    #[cfg(feature = "zbar_fork")]
+    fn test_set_uncertainty() {
+        let image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+
+        let scanner = ZBarImageScanner::new();
+        scanner.enable(ZBarSymbolType::ZBAR_QRCODE).unwrap();
+        scanner.set_uncertainty(ZBarSymbolType::ZBAR_QRCODE, 0).unwrap();
+        assert_eq!(
+            scanner.config_value(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_UNCERTAINTY),
+            Some(0)
+        );
+        scanner.scan_image(&image).unwrap();
+
+        assert_qrcode(image.first_symbol().unwrap());
+    }
+
+    #[test]
+    fn test_scan_roi_out_of_bounds() {
+        let image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+        assert!(ZBarImageScanner::new().scan_roi(&image, 100, 0, 51, 150).is_err());
+    }
+
     #[test]
     fn test_recycle_image() {
         let image = ZBarImage::from_path("test/code128.gif").unwrap();
@@ -188,6 +1544,22 @@ mod test {
         assert!(image.first_symbol().is_none());
     }
 
+    #[test]
+    fn test_scan_new_only() {
+        let image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+
+        let scanner = ImageScannerBuilder::new()
+            .with_config(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .with_cache(true)
+            .build()
+            .unwrap();
+
+        let first = scanner.scan_new_only(&image).unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].data(), "Hello World");
+        assert!(scanner.scan_new_only(&image).unwrap().is_empty());
+    }
+
     #[test]
     fn test_get_results() {
         let scanner = ImageScannerBuilder::new()
@@ -201,4 +1573,42 @@ mod test {
 
         assert_code128(scanner.results().unwrap().first_symbol().unwrap());
     }
+
+    #[test]
+    fn test_take_results() {
+        let image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+
+        let scanner = ImageScannerBuilder::new()
+            .with_config(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .with_cache(true)
+            .build()
+            .unwrap();
+        assert_eq!(scanner.scan_new_only(&image).unwrap().len(), 1);
+
+        let symbols = scanner.take_results().unwrap();
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].data(), "Hello World");
+        assert!(scanner.results().map_or(true, |symbols| symbols.size() == 0));
+
+        // the cache has been flushed, so the symbol is new again
+        assert_eq!(scanner.scan_new_only(&image).unwrap().len(), 1);
+        // but it is still enabled
+        assert!(scanner.scan_new_only(&image).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_take_results_without_cache() {
+        let image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+
+        let scanner = ImageScannerBuilder::new()
+            .with_config(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .build()
+            .unwrap();
+        scanner.scan_image(&image).unwrap();
+        assert_eq!(scanner.take_results().unwrap().len(), 1);
+
+        // the cache stays disabled, so every scan reports the symbol as new
+        assert_eq!(scanner.scan_new_only(&image).unwrap().len(), 1);
+        assert_eq!(scanner.scan_new_only(&image).unwrap().len(), 1);
+    }
 }
@@ -6,10 +6,16 @@
 
 #![cfg_attr(feature = "cargo-clippy", warn(cast_ptr_alignment))]
 
+#[cfg(feature = "from_image")]
+extern crate exif;
 #[cfg(feature = "from_image")]
 extern crate image as image_crate;
 #[macro_use]
 extern crate log;
+#[cfg(feature = "nokhwa")]
+extern crate nokhwa;
+#[cfg(feature = "async")]
+extern crate tokio;
 
 pub use ffi::{
     zbar_color_e as ZBarColor,
@@ -26,7 +32,7 @@ use std::{
     error::Error,
     ffi::{
         CStr,
-        OsString,
+        CString,
     },
     fmt,
     mem,
@@ -34,12 +40,20 @@ use std::{
         c_char,
         c_void
     },
+    sync::atomic::{
+        AtomicI32,
+        Ordering
+    },
 };
 
+pub mod config;
 pub mod decoder;
+/// Raw bindings to the zbar library generated by bindgen.
+///
+/// Only needed for interop with other ZBar-based C code, see the `as_raw` methods.
 #[allow(dead_code)]
 #[cfg_attr(feature = "cargo-clippy", allow(clippy))]
-mod ffi;
+pub mod ffi;
 pub mod format;
 pub mod image;
 pub mod symbol;
@@ -50,33 +64,39 @@ pub mod prelude;
 
 pub type ZBarResult<T> = Result<T, ZBarErrorType>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum ZBarErrorType {
     Simple(i32),
     Complex(ZBarError)
 }
 impl Error for ZBarErrorType {}
 impl fmt::Display for ZBarErrorType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ZBarErrorType::Simple(e)  => write!(f, "ZBar simple error {}", e),
+            ZBarErrorType::Complex(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl fmt::Display for ZBarError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use ZBarError::*;
 
         match *self {
-            ZBarErrorType::Simple(e)  => write!(f, "ZBar simple error {}", e),
-            ZBarErrorType::Complex(e) => match e {
-                ZBAR_ERR_NOMEM => write!(f, "out of memory"),
-                ZBAR_ERR_INTERNAL => write!(f, "internal library error"),
-                ZBAR_ERR_UNSUPPORTED => write!(f, "unsupported request"),
-                ZBAR_ERR_INVALID => write!(f, "invalid request"),
-                ZBAR_ERR_LOCKING => write!(f, "system error"),
-                ZBAR_ERR_SYSTEM => write!(f, "locking error"),
-                ZBAR_ERR_BUSY => write!(f, "all resources busy "),
-                ZBAR_ERR_XDISPLAY => write!(f, "X11 display error"),
-                ZBAR_ERR_XPROTO => write!(f, "X11 protocol error"),
-                ZBAR_ERR_CLOSED => write!(f, "output window is closed"),
-                ZBAR_ERR_WINAPI => write!(f, "windows system error"),
-                ZBAR_ERR_NUM => write!(f, "number of error codes"),
-                ZBAR_OK => write!(f, "success")
-             }
+            ZBAR_ERR_NOMEM => write!(f, "out of memory"),
+            ZBAR_ERR_INTERNAL => write!(f, "internal library error"),
+            ZBAR_ERR_UNSUPPORTED => write!(f, "unsupported request"),
+            ZBAR_ERR_INVALID => write!(f, "invalid request"),
+            ZBAR_ERR_SYSTEM => write!(f, "system error"),
+            ZBAR_ERR_LOCKING => write!(f, "locking error"),
+            ZBAR_ERR_BUSY => write!(f, "all resources busy "),
+            ZBAR_ERR_XDISPLAY => write!(f, "X11 display error"),
+            ZBAR_ERR_XPROTO => write!(f, "X11 protocol error"),
+            ZBAR_ERR_CLOSED => write!(f, "output window is closed"),
+            ZBAR_ERR_WINAPI => write!(f, "windows system error"),
+            ZBAR_ERR_NUM => write!(f, "number of error codes"),
+            ZBAR_OK => write!(f, "success")
         }
     }
 }
@@ -85,6 +105,29 @@ impl From<i32> for ZBarErrorType {
     fn from(error: i32) -> Self { ZBarErrorType::Complex(unsafe { mem::transmute(error) } ) }
 }
 
+/// The reason a config string couldn't be parsed by `parse_config_detailed`, carrying the
+/// offending part of the string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigParseError {
+    UnknownSymbology(String),
+    UnknownConfig(String),
+    InvalidValue(String),
+    ValueOutOfRange(String),
+}
+impl Error for ConfigParseError {}
+impl fmt::Display for ConfigParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConfigParseError::UnknownSymbology(ref s) => write!(f, "unknown symbology: {}", s),
+            ConfigParseError::UnknownConfig(ref s) => write!(f, "unknown config: {}", s),
+            ConfigParseError::InvalidValue(ref s) => write!(f, "value is not a number: {}", s),
+            ConfigParseError::ValueOutOfRange(ref s) => write!(
+                f, "value doesn't fit in 32 bits: {}", s
+            ),
+        }
+    }
+}
+
 pub fn version() -> (u32, u32) {
     unsafe {
         let mut version = (0, 0);
@@ -93,14 +136,151 @@ pub fn version() -> (u32, u32) {
     }
 }
 
+/// The last verbosity set through this crate. ZBar itself offers no getter.
+static VERBOSITY: AtomicI32 = AtomicI32::new(0);
+
 pub fn set_verbosity(verbosity: i32) {
+    VERBOSITY.store(verbosity, Ordering::SeqCst);
     unsafe { ffi::zbar_set_verbosity(verbosity) }
 }
 
 pub fn increase_verbosity() {
+    // mirrors the behavior of zbar_increase_verbosity
+    let verbosity = VERBOSITY.load(Ordering::SeqCst);
+    VERBOSITY.store(if verbosity == 0 { 1 } else { verbosity << 1 }, Ordering::SeqCst);
     unsafe { ffi::zbar_increase_verbosity() }
 }
 
+/// Returns the verbosity last set by `set_verbosity`, `increase_verbosity` or
+/// `verbosity_scope`.
+///
+/// Changes made to ZBar's verbosity by other means are not reflected.
+pub fn verbosity() -> i32 { VERBOSITY.load(Ordering::SeqCst) }
+
+/// Sets the verbosity until the returned guard is dropped.
+///
+/// The previous verbosity is restored afterwards, so a temporary increase for debugging does not
+/// leak into the rest of the program.
+///
+/// # Examples
+///
+/// ```
+/// use zbars::{verbosity, verbosity_scope};
+///
+/// {
+///     let _guard = verbosity_scope(10);
+///     assert_eq!(verbosity(), 10);
+/// }
+/// assert_eq!(verbosity(), 0);
+/// ```
+pub fn verbosity_scope(level: i32) -> VerbosityGuard {
+    let previous = verbosity();
+    set_verbosity(level);
+    VerbosityGuard { previous }
+}
+
+/// Restores the previous verbosity on `Drop`. See `verbosity_scope`.
+#[derive(Debug)]
+#[must_use]
+pub struct VerbosityGuard {
+    previous: i32,
+}
+impl Drop for VerbosityGuard {
+    fn drop(&mut self) { set_verbosity(self.previous) }
+}
+
+/// Returns every decodable `ZBarSymbolType`.
+///
+/// `ZBAR_NONE`, `ZBAR_PARTIAL` and the composite/add-on flags are left out. Use `symbol_name`
+/// to get a printable name for each entry.
+///
+/// # Examples
+///
+/// ```
+/// use zbars::{all_symbol_types, symbol_name};
+///
+/// for symbol_type in all_symbol_types() {
+///     println!("{}", symbol_name(*symbol_type));
+/// }
+/// ```
+pub fn all_symbol_types() -> &'static [ZBarSymbolType] { SYMBOL_TYPES }
+
+#[cfg(not(feature = "zbar_fork"))]
+const SYMBOL_TYPES: &[ZBarSymbolType] = &[
+    ZBarSymbolType::ZBAR_EAN8,
+    ZBarSymbolType::ZBAR_UPCE,
+    ZBarSymbolType::ZBAR_ISBN10,
+    ZBarSymbolType::ZBAR_UPCA,
+    ZBarSymbolType::ZBAR_EAN13,
+    ZBarSymbolType::ZBAR_ISBN13,
+    ZBarSymbolType::ZBAR_I25,
+    ZBarSymbolType::ZBAR_CODE39,
+    ZBarSymbolType::ZBAR_PDF417,
+    ZBarSymbolType::ZBAR_QRCODE,
+    ZBarSymbolType::ZBAR_CODE128,
+];
+#[cfg(feature = "zbar_fork")]
+const SYMBOL_TYPES: &[ZBarSymbolType] = &[
+    ZBarSymbolType::ZBAR_EAN2,
+    ZBarSymbolType::ZBAR_EAN5,
+    ZBarSymbolType::ZBAR_EAN8,
+    ZBarSymbolType::ZBAR_UPCE,
+    ZBarSymbolType::ZBAR_ISBN10,
+    ZBarSymbolType::ZBAR_UPCA,
+    ZBarSymbolType::ZBAR_EAN13,
+    ZBarSymbolType::ZBAR_ISBN13,
+    ZBarSymbolType::ZBAR_I25,
+    ZBarSymbolType::ZBAR_DATABAR,
+    ZBarSymbolType::ZBAR_DATABAR_EXP,
+    ZBarSymbolType::ZBAR_CODABAR,
+    ZBarSymbolType::ZBAR_CODE39,
+    ZBarSymbolType::ZBAR_PDF417,
+    ZBarSymbolType::ZBAR_QRCODE,
+    ZBarSymbolType::ZBAR_CODE93,
+    ZBarSymbolType::ZBAR_CODE128,
+];
+
+/// Returns the `ZBarSymbolType` for ZBar's numeric symbol type value.
+///
+/// This is the inverse of `ZBarSymbol::symbol_type_value` and accepts `ZBAR_NONE`,
+/// `ZBAR_PARTIAL` and every type returned by `all_symbol_types`.
+///
+/// # Examples
+///
+/// ```
+/// use zbars::{symbol_type_from_value, ZBarSymbolType};
+///
+/// assert_eq!(symbol_type_from_value(64), Some(ZBarSymbolType::ZBAR_QRCODE));
+/// assert_eq!(symbol_type_from_value(-1), None);
+/// ```
+pub fn symbol_type_from_value(value: i32) -> Option<ZBarSymbolType> {
+    [ZBarSymbolType::ZBAR_NONE, ZBarSymbolType::ZBAR_PARTIAL]
+        .iter()
+        .chain(all_symbol_types())
+        .find(|symbol_type| **symbol_type as i32 == value)
+        .cloned()
+}
+
+/// Returns whether the linked ZBar library is able to decode the given `ZBarSymbolType`.
+///
+/// ZBar can be built without some of its decoders (e.g. QR). This tries to enable the
+/// symbology on a throwaway scanner and reports whether ZBar accepted it.
+///
+/// # Examples
+///
+/// ```
+/// use zbars::{is_symbology_available, ZBarSymbolType};
+///
+/// if !is_symbology_available(ZBarSymbolType::ZBAR_QRCODE) {
+///     println!("ZBar has been built without QR support");
+/// }
+/// ```
+pub fn is_symbology_available(symbol_type: ZBarSymbolType) -> bool {
+    image_scanner::ZBarImageScanner::new()
+        .set_config(symbol_type, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+        .is_ok()
+}
+
 pub fn symbol_name(symbol_type: ZBarSymbolType) -> &'static str {
     unsafe { from_cstr(ffi::zbar_get_symbol_name(symbol_type)) }
 }
@@ -124,9 +304,10 @@ pub fn parse_config(config_string: impl AsRef<str>) -> ZBarResult<(ZBarSymbolTyp
     let mut symbol_type = ZBarSymbolType::ZBAR_NONE;
     let mut config = ZBarConfig::ZBAR_CFG_ENABLE;
     let mut value = 0;
+    let config_string = as_cstring(config_string)?;
     unsafe {
         match ffi::zbar_parse_config(
-            as_char_ptr(config_string),
+            config_string.as_ptr(),
             &mut symbol_type as *mut ZBarSymbolType,
             &mut config as *mut ZBarConfig,
             &mut value as *mut i32,
@@ -137,14 +318,93 @@ pub fn parse_config(config_string: impl AsRef<str>) -> ZBarResult<(ZBarSymbolTyp
     }
 }
 
+/// Like `parse_config`, but reports which part of the config string is invalid.
+///
+/// Values are read like ZBar does: decimal, hexadecimal with a `0x` prefix or octal with a
+/// leading `0`, optionally signed. Unlike ZBar, characters after the number are rejected
+/// instead of being ignored.
+///
+/// # Examples
+///
+/// ```
+/// use zbars::{parse_config_detailed, ConfigParseError};
+///
+/// assert_eq!(
+///     parse_config_detailed("qrcode.enbale=1"),
+///     Err(ConfigParseError::UnknownConfig("enbale".to_owned()))
+/// );
+/// ```
+pub fn parse_config_detailed(
+    config_string: &str
+) -> Result<(ZBarSymbolType, ZBarConfig, i32), ConfigParseError>
+{
+    let (key, value) = match config_string.find('=') {
+        Some(i) => (&config_string[..i], Some(&config_string[i + 1..])),
+        None    => (config_string, None),
+    };
+    let (symbology, config) = match key.find('.') {
+        Some(i) => (Some(&key[..i]), &key[i + 1..]),
+        None    => (None, key),
+    };
+    if let Some(symbology) = symbology {
+        if parse_config(format!("{}.enable", symbology)).is_err() {
+            return Err(ConfigParseError::UnknownSymbology(symbology.to_owned()));
+        }
+    }
+    if parse_config(config).is_err() {
+        return Err(ConfigParseError::UnknownConfig(config.to_owned()));
+    }
+    if let Some(value) = value {
+        parse_config_value(value)?;
+    }
+    parse_config(config_string)
+        .map_err(|_| ConfigParseError::UnknownConfig(config_string.to_owned()))
+}
+
+/// Parses a config value with the base detection of `strtol` with base 0, which
+/// `zbar_parse_config` uses.
+fn parse_config_value(value: &str) -> Result<i32, ConfigParseError> {
+    let (negative, unsigned) = match value.as_bytes().first() {
+        Some(&b'-') => (true, &value[1..]),
+        Some(&b'+') => (false, &value[1..]),
+        _           => (false, value),
+    };
+    let (radix, digits) = if unsigned.starts_with("0x") || unsigned.starts_with("0X") {
+        (16, &unsigned[2..])
+    } else if unsigned.len() > 1 && unsigned.starts_with('0') {
+        (8, &unsigned[1..])
+    } else {
+        (10, unsigned)
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return Err(ConfigParseError::InvalidValue(value.to_owned()));
+    }
+    // only digits are left, so parsing can only fail on overflow
+    let magnitude = i64::from_str_radix(digits, radix)
+        .map_err(|_| ConfigParseError::ValueOutOfRange(value.to_owned()))?;
+    let signed = if negative { -magnitude } else { magnitude };
+    if signed < i64::from(i32::min_value()) || signed > i64::from(i32::max_value()) {
+        return Err(ConfigParseError::ValueOutOfRange(value.to_owned()));
+    }
+    Ok(signed as i32)
+}
+
 pub(crate) unsafe fn error_code(object: *const c_void) -> ZBarError {
     ffi::_zbar_get_error_code(object)
 }
 
+pub(crate) unsafe fn error_string(object: *const c_void) -> String {
+    CStr::from_ptr(ffi::_zbar_error_string(object, 0)).to_string_lossy().into_owned()
+}
+
 //pub fn addon_name()
 
-unsafe fn as_char_ptr(value: impl AsRef<str>) -> *const i8 {
-    OsString::from(value.as_ref()).to_str().unwrap().as_ptr() as *const i8
+/// Creates a NUL terminated copy of `value` to be passed to ZBar.
+///
+/// The `CString` has to be kept alive while ZBar uses the pointer. Returns `ZBAR_ERR_INVALID` if
+/// `value` contains a NUL byte.
+fn as_cstring(value: impl AsRef<str>) -> ZBarResult<CString> {
+    CString::new(value.as_ref()).map_err(|_| ZBarErrorType::Complex(ZBarError::ZBAR_ERR_INVALID))
 }
 
 unsafe fn from_cstr(ptr: *const c_char) -> &'static str { CStr::from_ptr(ptr).to_str().unwrap() }
@@ -159,12 +419,61 @@ mod test {
         assert_ne!(major + minor, 0);
     }
 
+    #[test]
+    fn test_verbosity_scope() {
+        let previous = verbosity();
+        {
+            let _guard = verbosity_scope(previous + 5);
+            assert_eq!(verbosity(), previous + 5);
+        }
+        assert_eq!(verbosity(), previous);
+    }
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!(ZBarError::ZBAR_ERR_SYSTEM.to_string(), "system error");
+        assert_eq!(ZBarError::ZBAR_ERR_LOCKING.to_string(), "locking error");
+        assert_eq!(
+            ZBarErrorType::Complex(ZBarError::ZBAR_ERR_INVALID).to_string(),
+            "invalid request"
+        );
+    }
+
     #[test]
     fn test_symbol_name() {
         assert_eq!(symbol_name(ZBarSymbolType::ZBAR_QRCODE), "QR-Code");
         assert_eq!(symbol_name(ZBarSymbolType::ZBAR_CODE128), "CODE-128");
     }
 
+    #[test]
+    fn test_all_symbol_types() {
+        assert!(all_symbol_types().contains(&ZBarSymbolType::ZBAR_QRCODE));
+        assert!(!all_symbol_types().contains(&ZBarSymbolType::ZBAR_NONE));
+        assert!(!all_symbol_types().contains(&ZBarSymbolType::ZBAR_PARTIAL));
+        all_symbol_types()
+            .iter()
+            .for_each(|symbol_type| assert!(!symbol_name(*symbol_type).is_empty()));
+    }
+
+    #[test]
+    fn test_symbol_type_from_value() {
+        assert_eq!(
+            symbol_type_from_value(ZBarSymbolType::ZBAR_QRCODE as i32),
+            Some(ZBarSymbolType::ZBAR_QRCODE)
+        );
+        assert_eq!(symbol_type_from_value(0), Some(ZBarSymbolType::ZBAR_NONE));
+        assert_eq!(symbol_type_from_value(3), None);
+        all_symbol_types().iter().for_each(|symbol_type| {
+            assert_eq!(symbol_type_from_value(*symbol_type as i32), Some(*symbol_type));
+        });
+    }
+
+    #[test]
+    fn test_is_symbology_available() {
+        assert!(is_symbology_available(ZBarSymbolType::ZBAR_QRCODE));
+        assert!(is_symbology_available(ZBarSymbolType::ZBAR_CODE128));
+    }
+
     #[test]
     fn test_parse_config() {
         assert_eq!(
@@ -177,4 +486,67 @@ mod test {
     fn test_parse_config_err() {
         assert!(parse_config("Not valid").is_err());
     }
+
+    #[test]
+    fn test_parse_config_nul() {
+        match parse_config("qrcode.enable=1\0") {
+            Err(ZBarErrorType::Complex(ZBarError::ZBAR_ERR_INVALID)) => {},
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_config_detailed() {
+        assert_eq!(
+            parse_config_detailed("qrcode.enable=1").unwrap(),
+            (ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+        );
+        assert_eq!(
+            parse_config_detailed("code128.min-length=4").unwrap(),
+            (ZBarSymbolType::ZBAR_CODE128, ZBarConfig::ZBAR_CFG_MIN_LEN, 4)
+        );
+    }
+
+    #[test]
+    fn test_parse_config_detailed_err() {
+        assert_eq!(
+            parse_config_detailed("qrcode.enbale=1"),
+            Err(ConfigParseError::UnknownConfig("enbale".to_owned()))
+        );
+        assert_eq!(
+            parse_config_detailed("qrkode.enable=1"),
+            Err(ConfigParseError::UnknownSymbology("qrkode".to_owned()))
+        );
+        assert_eq!(
+            parse_config_detailed("code128.min-length=99999999999"),
+            Err(ConfigParseError::ValueOutOfRange("99999999999".to_owned()))
+        );
+        assert_eq!(
+            parse_config_detailed("code128.min-length=four"),
+            Err(ConfigParseError::InvalidValue("four".to_owned()))
+        );
+        assert_eq!(
+            parse_config_detailed("code128.min-length=0x"),
+            Err(ConfigParseError::InvalidValue("0x".to_owned()))
+        );
+        assert_eq!(
+            parse_config_detailed("code128.min-length=0x100000000"),
+            Err(ConfigParseError::ValueOutOfRange("0x100000000".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_parse_config_detailed_bases() {
+        for &(config_string, value) in &[
+            ("code128.min-length=0x10", 16),
+            ("code128.min-length=0X1f", 31),
+            ("code128.min-length=010", 8),
+            ("code128.min-length=-0x10", -16),
+            ("code128.min-length=0", 0),
+        ] {
+            let expected = (ZBarSymbolType::ZBAR_CODE128, ZBarConfig::ZBAR_CFG_MIN_LEN, value);
+            assert_eq!(parse_config_detailed(config_string).unwrap(), expected);
+            assert_eq!(parse_config(config_string).unwrap(), expected);
+        }
+    }
 }
@@ -1,13 +1,22 @@
 pub use {
     format::{
         Format,
+        NV12,
         Y8,
         Y800,
+        YUYV,
+    },
+    image::{
+        SharedZBarImage,
+        ZBarImage,
     },
-    image::ZBarImage,
     image_scanner::ZBarImageScanner,
     processor::ZBarProcessor,
-    symbol::ZBarSymbol,
+    symbol::{
+        Orientation,
+        OwnedSymbol,
+        ZBarSymbol,
+    },
     symbol_set::ZBarSymbolSet,
     ZBarConfig,
     ZBarSymbolType,
@@ -1,42 +1,255 @@
 use {
-    as_char_ptr,
+    as_cstring,
+    error_code,
+    error_string,
     ffi,
     format::Format,
     image::ZBarImage,
+    symbol::OwnedSymbol,
     symbol_set::ZBarSymbolSet,
     ZBarConfig,
+    ZBarError,
     ZBarErrorType,
     ZBarResult,
     ZBarSymbolType,
 };
-use std::ptr;
+use std::{
+    collections::HashSet,
+    error::Error,
+    fmt,
+    fs::File,
+    io::{
+        self,
+        ErrorKind
+    },
+    os::raw::c_void,
+    panic::{
+        self,
+        AssertUnwindSafe
+    },
+    ptr,
+    sync::{
+        atomic::{
+            AtomicBool,
+            Ordering
+        },
+        mpsc::{
+            self,
+            Receiver,
+            Sender
+        },
+        Mutex
+    },
+    time::Duration,
+};
+#[cfg(feature = "async")]
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{
+        Context,
+        Poll
+    },
+};
+#[cfg(feature = "async")]
+use tokio::task::{
+    self,
+    JoinHandle
+};
+
+/// State shared with the data handler, which ZBar may invoke from its worker thread.
+#[derive(Default)]
+struct HandlerState {
+    frame_size: Mutex<Option<(u32, u32)>>,
+    sender: Mutex<Option<Sender<Vec<OwnedSymbol>>>>,
+    dedup: AtomicBool,
+    // type and data of the symbols of the previous frame, only tracked with deduplication
+    previous: Mutex<HashSet<(ZBarSymbolType, Vec<u8>)>>,
+}
 
+unsafe extern fn data_handler(image: *mut ffi::zbar_image_s, userdata: *const c_void) {
+    // unwinding into ZBar's worker thread is undefined behaviour
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        handle_data(image, &*(userdata as *const HandlerState))
+    }));
+    if result.is_err() {
+        error!("panic in the processor data handler");
+    }
+}
+
+unsafe fn handle_data(image: *mut ffi::zbar_image_s, state: &HandlerState) {
+    if let Ok(mut frame_size) = state.frame_size.lock() {
+        *frame_size = Some((ffi::zbar_image_get_width(image), ffi::zbar_image_get_height(image)));
+    }
+    if let Ok(sender) = state.sender.lock() {
+        if let Some(ref sender) = *sender {
+            let symbols = ZBarSymbolSet::from_raw(ffi::zbar_image_get_symbols(image), image)
+                .map(|symbols| symbols.iter().map(OwnedSymbol::from).collect::<Vec<_>>())
+                .unwrap_or_default();
+            let symbols = match state.previous.lock() {
+                Ok(ref mut previous) if state.dedup.load(Ordering::SeqCst) => {
+                    deduplicated(symbols, previous)
+                }
+                _ => symbols,
+            };
+            // the receiver may have been dropped already
+            let _ = sender.send(symbols);
+        }
+    }
+}
+
+/// Removes the symbols that were already part of the `previous` results and records the
+/// current ones in their place.
+fn deduplicated(
+    symbols: Vec<OwnedSymbol>,
+    previous: &mut HashSet<(ZBarSymbolType, Vec<u8>)>
+) -> Vec<OwnedSymbol>
+{
+    let current = symbols
+        .iter()
+        .map(|symbol| (symbol.symbol_type(), symbol.data_bytes().to_vec()))
+        .collect::<HashSet<_>>();
+    let symbols = symbols
+        .into_iter()
+        .filter(|symbol| !previous.contains(&(symbol.symbol_type(), symbol.data_bytes().to_vec())))
+        .collect();
+    *previous = current;
+    symbols
+}
+
+/// Processes images and video frames with optional display of the results.
+///
+/// The underlying ZBar processor is not exposed through `Deref`, so it can't be destroyed or
+/// otherwise misused by accident. Use `as_raw` if raw access is actually needed.
+///
+/// ```compile_fail
+/// use zbars::{ffi, prelude::*};
+///
+/// let processor = ZBarProcessor::new(false);
+/// unsafe { ffi::zbar_processor_destroy(*processor) };
+/// ```
+///
+/// # Thread safety
+///
+/// `ZBarProcessor` is `Send` and `Sync`. ZBar serializes the processor functions behind its
+/// own processor lock, except for changing the scanner configuration. Hence `set_config` and
+/// `results_channel` take `&mut self`, so they can't race with other calls on a shared
+/// processor.
+///
+/// ```compile_fail
+/// use std::sync::Arc;
+/// use zbars::prelude::*;
+///
+/// let processor = Arc::new(ZBarProcessor::new(true));
+/// processor.set_config(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1);
+/// ```
 pub struct ZBarProcessor {
     processor: *mut ffi::zbar_processor_s,
+    // Boxed to keep its address stable as ZBar holds a pointer to it. Dropped after the
+    // processor has been destroyed.
+    handler: Box<HandlerState>,
+    // ZBar offers no getter for this, so it is tracked by `set_active`
+    active: AtomicBool,
 }
 impl ZBarProcessor {
     pub fn new(threaded: bool) -> Self {
         let mut processor = ZBarProcessor {
-            processor: unsafe { ffi::zbar_processor_create(threaded as i32) }
+            processor: unsafe { ffi::zbar_processor_create(threaded as i32) },
+            handler: Box::new(HandlerState::default()),
+            active: AtomicBool::new(false),
         };
+        unsafe {
+            ffi::zbar_processor_set_data_handler(
+                processor.processor,
+                Some(data_handler),
+                &*processor.handler as *const HandlerState as *const c_void
+            );
+        }
         processor.set_config(ZBarSymbolType::ZBAR_NONE, ZBarConfig::ZBAR_CFG_ENABLE, 0)
             // save to unwrap here
             .unwrap();
         processor
     }
+    /// Creates a `ZBarProcessor` that never spawns ZBar's worker thread.
+    ///
+    /// All processing takes place synchronously on the thread calling `process_one`,
+    /// `process_image` or `user_wait`. So callers can pin processing to a thread of their
+    /// choice by running a `process_one` loop there. This is the same as `new(false)`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::thread;
+    /// use zbars::prelude::*;
+    ///
+    /// thread::spawn(|| {
+    ///     let processor = ZBarProcessor::new_unthreaded_polling();
+    ///     processor.init("/dev/video0", false).unwrap();
+    ///     loop {
+    ///         if let Some(symbols) = processor.process_one(1000).unwrap() {
+    ///             println!("{}", symbols.first_symbol().unwrap().data());
+    ///         }
+    ///     }
+    /// });
+    /// ```
+    pub fn new_unthreaded_polling() -> Self { Self::new(false) }
     pub fn builder() -> ZBarProcessorBuilder { ZBarProcessorBuilder::new() }
+    /// Returns the underlying ZBar processor for interop with other ZBar-based C code.
+    ///
+    /// The pointer is owned by this `ZBarProcessor` and only valid as long as it is alive.
+    /// Callers must not destroy it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::ffi;
+    /// use zbars::prelude::*;
+    ///
+    /// let processor = ZBarProcessor::new(false);
+    /// let raw: *mut ffi::zbar_processor_s = processor.as_raw();
+    /// assert!(!raw.is_null());
+    /// ```
+    pub fn as_raw(&self) -> *mut ffi::zbar_processor_s { self.processor }
 
     //Tested
     pub fn init(&self, video_device: impl AsRef<str>, enable_display: bool) -> ZBarResult<()> {
+        let video_device = as_cstring(video_device)?;
         match unsafe {
-            ffi::zbar_processor_init(
-                self.processor, as_char_ptr(video_device), enable_display as i32,
-            )
+            ffi::zbar_processor_init(self.processor, video_device.as_ptr(), enable_display as i32)
         } {
             0 => Ok(()),
             e => Err(ZBarErrorType::Simple(e)),
         }
     }
+    /// Like `init`, but tells why the video device couldn't be opened.
+    ///
+    /// ZBar only reports a generic system error if the device can't be opened. In that case the
+    /// device is opened once more, read-only and without any further access, to distinguish a
+    /// missing device from missing permissions. A busy device is only detected on Linux.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use zbars::prelude::*;
+    /// use zbars::processor::ProcessorInitError;
+    ///
+    /// let processor = ZBarProcessor::builder().build().unwrap();
+    /// match processor.init_detailed("/dev/video0", false) {
+    ///     Ok(())                                    => println!("ready"),
+    ///     Err(ProcessorInitError::PermissionDenied) => println!("add yourself to 'video'"),
+    ///     Err(e)                                    => println!("{}", e),
+    /// }
+    /// ```
+    pub fn init_detailed(
+        &self,
+        video_device: impl AsRef<str>,
+        enable_display: bool
+    ) -> Result<(), ProcessorInitError>
+    {
+        self.init(&video_device, enable_display)
+            .map_err(|_| init_error(self.last_error(), video_device.as_ref()))
+    }
     //Tested
     pub fn request_size(&self, width: u32, height: u32) -> ZBarResult<()> {
         match unsafe { ffi::zbar_processor_request_size(self.processor, width, height) } {
@@ -44,6 +257,65 @@ impl ZBarProcessor {
             e => Err(ZBarErrorType::Simple(e)),
         }
     }
+    /// Returns the size of the last frame in which symbols have been decoded.
+    ///
+    /// ZBar may silently clamp the size given to `request_size` to what the video device
+    /// supports, but it doesn't expose the negotiated capture size. The size is therefore taken
+    /// from the frames reported to the processor's data handler. ZBar only reports frames or
+    /// images that contain symbols, so this returns `None` until the first symbol has been
+    /// decoded, even if video has been initialized.
+    pub fn last_frame_size(&self) -> Option<(u32, u32)> {
+        self.handler.frame_size.lock().ok().and_then(|frame_size| *frame_size)
+    }
+    /// Returns a channel receiving the symbols of every frame ZBar reports to the processor's
+    /// data handler.
+    ///
+    /// The handler is invoked for each processed image or video frame that contains symbols,
+    /// possibly on ZBar's worker thread. Calling this again replaces the previous channel, which
+    /// is closed as well as soon as the processor is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::thread;
+    /// use zbars::prelude::*;
+    ///
+    /// let mut processor = ZBarProcessor::builder().threaded(true).build().unwrap();
+    /// let results = processor.results_channel();
+    /// processor.init("/dev/video0", false).unwrap();
+    /// processor.set_active(true).unwrap();
+    ///
+    /// thread::spawn(move || {
+    ///     for symbols in results {
+    ///         symbols.iter().for_each(|symbol| println!("{}", symbol.data()));
+    ///     }
+    /// });
+    /// processor.user_wait(-1).unwrap();
+    /// ```
+    pub fn results_channel(&mut self) -> Receiver<Vec<OwnedSymbol>> {
+        let (sender, receiver) = mpsc::channel();
+        if let Ok(mut current) = self.handler.sender.lock() {
+            *current = Some(sender);
+        }
+        receiver
+    }
+    /// Enables or disables deduplication of the results sent to `results_channel`.
+    ///
+    /// With deduplication enabled, symbols that were already part of the results sent for the
+    /// previous frame are left out, so each symbol is reported once while it stays in view.
+    /// Only the previous frame is compared, so a symbol that leaves the view and comes back is
+    /// reported again. This is handy for video but drops repeated results of single image batch
+    /// scans, so it is disabled by default.
+    ///
+    /// This is not ZBar's scanner result cache. ZBar doesn't expose the image scanner used
+    /// inside the processor and enables its cache only while video is active (see
+    /// `set_active`), so the deduplication is done by `ZBarProcessor` itself.
+    pub fn dedup_results(&self, enable: bool) {
+        self.handler.dedup.store(enable, Ordering::SeqCst);
+        if let Ok(mut previous) = self.handler.previous.lock() {
+            previous.clear();
+        }
+    }
     //Tested
     pub fn request_interface(&self, version: i32) -> ZBarResult<()> {
         match unsafe { ffi::zbar_processor_request_interface(self.processor, version) } {
@@ -58,6 +330,14 @@ impl ZBarProcessor {
             e => Err(ZBarErrorType::Simple(e)),
         }
     }
+    /// Like `request_interface`, but takes a typed `Interface`.
+    pub fn request_interface_typed(&self, interface: Interface) -> ZBarResult<()> {
+        self.request_interface(interface.into())
+    }
+    /// Like `request_iomode`, but takes a typed `IoMode`.
+    pub fn request_iomode_typed(&self, iomode: IoMode) -> ZBarResult<()> {
+        self.request_iomode(iomode.into())
+    }
     pub fn force_format(&self, input_format: Format, output_format: Format) -> ZBarResult<()> {
         match unsafe {
             ffi::zbar_processor_force_format(
@@ -100,19 +380,56 @@ impl ZBarProcessor {
             e => Err(ZBarErrorType::Simple(e)),
         }
     }
+    /// Starts or stops video streaming.
+    ///
+    /// ZBar couples the result cache of the processor's internal image scanner to streaming:
+    /// it is enabled while video is active, so every symbol is reported once, and disabled
+    /// otherwise.
     pub fn set_active(&self, active: bool) -> ZBarResult<bool> {
         match unsafe { ffi::zbar_processor_set_active(self.processor, active as i32) } {
-            0 => Ok(false),
-            1 => Ok(true),
+            e @ 0 | e @ 1 => {
+                self.active.store(active, Ordering::SeqCst);
+                Ok(e == 1)
+            }
             e => Err(ZBarErrorType::Simple(e)),
         }
     }
+    /// Returns whether the window is visible and video streaming is active.
+    ///
+    /// ZBar doesn't report whether video is active, so this reflects the last successful call
+    /// to `set_active`.
+    pub fn state(&self) -> ZBarResult<ProcessorState> {
+        self.is_visible().map(|visible| {
+            ProcessorState { visible, active: self.active.load(Ordering::SeqCst) }
+        })
+    }
     pub fn get_results(&self) -> Option<ZBarSymbolSet> {
         ZBarSymbolSet::from_raw(
             unsafe { ffi::zbar_processor_get_results(self.processor) }, ptr::null_mut()
         )
     }
 
+    /// Returns the code of the last error that occurred in this processor.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use zbars::prelude::*;
+    ///
+    /// let processor = ZBarProcessor::builder().build().unwrap();
+    /// if processor.init("/dev/video0", false).is_err() {
+    ///     println!("{}: {}", processor.last_error(), processor.last_error_string());
+    /// }
+    /// ```
+    pub fn last_error(&self) -> ZBarError {
+        unsafe { error_code(self.processor as *const c_void) }
+    }
+    /// Returns ZBar's detailed description of the last error, including the failing device
+    /// operation and system error.
+    pub fn last_error_string(&self) -> String {
+        unsafe { error_string(self.processor as *const c_void) }
+    }
+
     // Tested
     pub fn user_wait(&self, timeout: i32) -> ZBarResult<i32> {
         match unsafe { ffi::zbar_processor_user_wait(self.processor, timeout) } {
@@ -121,9 +438,108 @@ impl ZBarProcessor {
         }
     }
 
+    /// Like `user_wait`, but takes the timeout as `Duration`. `None` waits forever.
+    ///
+    /// Returns `ZBAR_ERR_INVALID` if the timeout exceeds `i32::MAX` milliseconds.
+    pub fn user_wait_timeout(&self, timeout: Option<Duration>) -> ZBarResult<i32> {
+        self.user_wait(timeout_millis(timeout)?)
+    }
+
+    /// Waits for input on the display window and returns it as `UserEvent`. `None` waits
+    /// forever.
+    ///
+    /// ZBar returns key presses and mouse clicks as one integer, which is ambiguous for the
+    /// values 1 to 3 (see `UserEvent::MouseClick`). Returns `ZBAR_ERR_INVALID` if the timeout
+    /// exceeds `i32::MAX` milliseconds.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use zbars::processor::{UserEvent, ZBarProcessor};
+    ///
+    /// let processor = ZBarProcessor::builder().build().unwrap();
+    /// processor.init("/dev/video0", true).unwrap();
+    /// processor.set_visible(true).unwrap();
+    /// loop {
+    ///     match processor.wait_event(None).unwrap() {
+    ///         UserEvent::KeyPress(key) if key == i32::from(b'q') => break,
+    ///         UserEvent::WindowClosed                          => break,
+    ///         event                                            => println!("{:?}", event),
+    ///     }
+    /// }
+    /// ```
+    pub fn wait_event(&self, timeout: Option<Duration>) -> ZBarResult<UserEvent> {
+        let result = unsafe {
+            ffi::zbar_processor_user_wait(self.processor, timeout_millis(timeout)?)
+        };
+        user_event(result, self.last_error())
+    }
+
     // Tested
     pub fn process_one(&self, timeout: i32) -> ZBarResult<Option<ZBarSymbolSet>> {
-        match unsafe { ffi::zbar_process_one(self.processor, timeout) } {
+        self.process_one_results(unsafe { ffi::zbar_process_one(self.processor, timeout) })
+    }
+
+    /// Like `process_one`, but takes the timeout as `Duration`. `None` waits forever.
+    ///
+    /// Returns `ZBAR_ERR_INVALID` if the timeout exceeds `i32::MAX` milliseconds.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use zbars::prelude::*;
+    ///
+    /// let processor = ZBarProcessor::builder().build().unwrap();
+    /// processor.init("/dev/video0", false).unwrap();
+    /// processor.set_visible(true).unwrap();
+    /// match processor.process_one_timeout(Some(Duration::from_secs(5))).unwrap() {
+    ///     Some(symbols) => println!("{}", symbols.first_symbol().unwrap().data()),
+    ///     None          => println!("timeout expired"),
+    /// }
+    /// ```
+    pub fn process_one_timeout(
+        &self,
+        timeout: Option<Duration>
+    ) -> ZBarResult<Option<ZBarSymbolSet>>
+    {
+        self.process_one(timeout_millis(timeout)?)
+    }
+
+    /// Calls `process_one` until `accept` returns `true` for the decoded symbols.
+    ///
+    /// Gives up and returns `None` after `max_attempts` attempts, each waiting up to
+    /// `per_attempt_timeout` milliseconds. Attempts that time out count as well. Errors are
+    /// returned immediately.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use zbars::prelude::*;
+    ///
+    /// let processor = ZBarProcessor::builder()
+    ///     .with_config(ZBarSymbolType::ZBAR_EAN13, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+    ///     .build()
+    ///     .unwrap();
+    /// processor.init("/dev/video0", true).unwrap();
+    /// processor.set_visible(true).unwrap();
+    /// let symbols = processor.process_until(10, 1000, |symbols| {
+    ///     symbols.iter().any(|symbol| symbol.data().starts_with("400"))
+    /// }).unwrap();
+    /// ```
+    pub fn process_until<F>(
+        &self,
+        max_attempts: u32,
+        per_attempt_timeout: i32,
+        accept: F
+    ) -> ZBarResult<Option<ZBarSymbolSet>>
+        where F: Fn(&ZBarSymbolSet) -> bool
+    {
+        retry_until(max_attempts, || self.process_one(per_attempt_timeout), accept)
+    }
+
+    fn process_one_results(&self, result: i32) -> ZBarResult<Option<ZBarSymbolSet>> {
+        match result {
             -1 => Err(ZBarErrorType::Simple(-1)),
             0  => Ok(None),
             _  => Ok(self.get_results())
@@ -131,7 +547,18 @@ impl ZBarProcessor {
     }
 
     // Tested
+    /// Processes the image like `ZBarImageScanner::scan_image` and displays it if the window is
+    /// visible.
+    ///
+    /// Images with padded rows (see `ZBarImage::set_stride`) are processed from a copy without
+    /// padding and the symbols found are attached to the given image.
     pub fn process_image<T>(&self, image: &ZBarImage<T>) -> ZBarResult<ZBarSymbolSet> {
+        if let Some(packed) = image.packed() {
+            let symbols = self.process_image(&packed)?;
+            unsafe { ffi::zbar_image_set_symbols(image.image(), symbols.symbol_set()) };
+            // symbols can be unwrapped because they have just been set
+            return Ok(image.symbols().unwrap());
+        }
         match unsafe { ffi::zbar_process_image(self.processor, image.image()) } {
             -1 => Err(ZBarErrorType::Simple(-1)),
             _  => Ok(image.symbols().unwrap()), // symbols can be unwrapped because image is surely scanned
@@ -153,8 +580,9 @@ impl ZBarProcessor {
     /// processor.set_control("contrast", 50).unwrap();
     /// ```
     pub fn set_control(&self, control_name: impl AsRef<str>, value: i32) -> ZBarResult<()> {
+        let control_name = as_cstring(control_name)?;
         match unsafe {
-            ffi::zbar_processor_set_control(self.processor, as_char_ptr(control_name), value)
+            ffi::zbar_processor_set_control(self.processor, control_name.as_ptr(), value)
         } {
             0 => Ok(()),
             e => Err(ZBarErrorType::Simple(e))
@@ -174,10 +602,11 @@ impl ZBarProcessor {
     /// println!("contrast: {}", processor.control("contrast").unwrap());
     /// ```
     pub fn control(&self, control_name: impl AsRef<str>) -> ZBarResult<i32> {
+        let control_name = as_cstring(control_name)?;
         let mut value = 0;
         match unsafe {
             ffi::zbar_processor_get_control(
-                self.processor, as_char_ptr(control_name), &mut value as *mut i32
+                self.processor, control_name.as_ptr(), &mut value as *mut i32
             )
         } {
             0 => Ok(value),
@@ -186,16 +615,253 @@ impl ZBarProcessor {
     }
 
 }
+#[cfg(feature = "async")]
+impl ZBarProcessor {
+    /// Like `process_one`, but runs the blocking call on tokio's blocking thread pool.
+    ///
+    /// The processor is moved into the blocking task, which is possible because `ZBarProcessor`
+    /// is `Send` and `Sync`. The task is only spawned when the returned future is first polled,
+    /// so the future may be created outside of a tokio runtime, but it has to be polled within
+    /// one, e.g. by `Runtime::block_on`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// extern crate tokio;
+    /// extern crate zbars;
+    ///
+    /// use std::sync::Arc;
+    /// use zbars::prelude::*;
+    ///
+    /// fn main() {
+    ///     let processor = Arc::new(ZBarProcessor::builder().threaded(true).build().unwrap());
+    ///     processor.init("/dev/video0", false).unwrap();
+    ///
+    ///     let runtime = tokio::runtime::Runtime::new().unwrap();
+    ///     match runtime.block_on(processor.process_one_async(5000)).unwrap() {
+    ///         Some(symbols) => println!("{}", symbols.first_symbol().unwrap().data()),
+    ///         None          => println!("timeout expired"),
+    ///     }
+    /// }
+    /// ```
+    pub fn process_one_async(self: Arc<Self>, timeout: i32) -> ProcessOne {
+        ProcessOne { processor: self, timeout, handle: None }
+    }
+}
+
+/// The reason `ZBarProcessor::init_detailed` failed.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ProcessorInitError {
+    DeviceNotFound,
+    PermissionDenied,
+    Busy,
+    Unsupported,
+    /// Any other error, holding the ZBar error code.
+    Other(i32),
+}
+impl Error for ProcessorInitError {}
+impl fmt::Display for ProcessorInitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ProcessorInitError::DeviceNotFound => write!(f, "video device not found"),
+            ProcessorInitError::PermissionDenied => write!(
+                f, "permission denied to open the video device"
+            ),
+            ProcessorInitError::Busy => write!(f, "video device is busy"),
+            ProcessorInitError::Unsupported => write!(f, "video device is not supported"),
+            ProcessorInitError::Other(e) => write!(f, "ZBar error {}", e),
+        }
+    }
+}
+
+/// Maps the error of a failed `init` to a `ProcessorInitError`.
+fn init_error(error: ZBarError, video_device: &str) -> ProcessorInitError {
+    match error {
+        ZBarError::ZBAR_ERR_BUSY        => ProcessorInitError::Busy,
+        ZBarError::ZBAR_ERR_UNSUPPORTED => ProcessorInitError::Unsupported,
+        ZBarError::ZBAR_ERR_SYSTEM      => {
+            // read-only, so probing has no side effects on the device
+            match File::open(video_device) {
+                Err(ref e) if e.kind() == ErrorKind::NotFound => ProcessorInitError::DeviceNotFound,
+                Err(ref e) if e.kind() == ErrorKind::PermissionDenied => {
+                    ProcessorInitError::PermissionDenied
+                }
+                Err(ref e) if is_busy(e) => ProcessorInitError::Busy,
+                _ => ProcessorInitError::Other(error as i32),
+            }
+        }
+        _ => ProcessorInitError::Other(error as i32),
+    }
+}
+
+/// Input on the display window of a `ZBarProcessor`, see `ZBarProcessor::wait_event`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum UserEvent {
+    /// A key was pressed, holding the character code of the key.
+    ///
+    /// Keys with the codes 1 to 3 (`Ctrl-A` to `Ctrl-C`) are reported as `MouseClick`.
+    KeyPress(i32),
+    /// A mouse button was clicked, holding the button number (1 to 3).
+    ///
+    /// ZBar reports mouse buttons as the codes 1 to 3 through the same channel as keys, so the
+    /// key presses `Ctrl-A` to `Ctrl-C` can't be told apart from clicks and end up here as
+    /// well. The pointer position is not passed on by ZBar, hence only the button is known.
+    MouseClick(i32),
+    /// The timeout expired without input.
+    Timeout,
+    /// The display window was closed or is not visible.
+    WindowClosed,
+}
+
+/// Decodes the `result` of `zbar_processor_user_wait` and the processor's last `error`.
+fn user_event(result: i32, error: ZBarError) -> ZBarResult<UserEvent> {
+    match (result, error) {
+        (0, _)                           => Ok(UserEvent::Timeout),
+        // also Ctrl-A to Ctrl-C, which ZBar reports with the same values
+        (button @ 1..=3, _)              => Ok(UserEvent::MouseClick(button)),
+        (key, _) if key > 0              => Ok(UserEvent::KeyPress(key)),
+        (_, ZBarError::ZBAR_ERR_CLOSED)  => Ok(UserEvent::WindowClosed),
+        (result, _)                      => Err(ZBarErrorType::Simple(result)),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn is_busy(error: &io::Error) -> bool {
+    // EBUSY
+    error.raw_os_error() == Some(16)
+}
+#[cfg(not(target_os = "linux"))]
+fn is_busy(_: &io::Error) -> bool { false }
+
+/// Calls `attempt` up to `max_attempts` times until it yields a result accepted by `accept`.
+fn retry_until<T, A, F>(max_attempts: u32, mut attempt: A, accept: F) -> ZBarResult<Option<T>>
+    where A: FnMut() -> ZBarResult<Option<T>>,
+          F: Fn(&T) -> bool
+{
+    for _ in 0..max_attempts {
+        match attempt()? {
+            Some(result) if accept(&result) => return Ok(Some(result)),
+            _                               => {}
+        }
+    }
+    Ok(None)
+}
+
+/// Converts a timeout to milliseconds where `-1` means waiting forever as expected by ZBar.
+fn timeout_millis(timeout: Option<Duration>) -> ZBarResult<i32> {
+    match timeout {
+        None          => Ok(-1),
+        Some(timeout) => {
+            let millis = timeout.as_secs()
+                .checked_mul(1000)
+                .and_then(|millis| millis.checked_add(u64::from(timeout.subsec_millis())))
+                .filter(|millis| *millis <= i32::max_value() as u64);
+            match millis {
+                Some(millis) => Ok(millis as i32),
+                None         => Err(ZBarErrorType::Complex(ZBarError::ZBAR_ERR_INVALID)),
+            }
+        }
+    }
+}
+
+/// The video interface requested by `ZBarProcessor::request_interface_typed`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Interface {
+    /// Let ZBar probe for the best interface.
+    Auto,
+    V4L1,
+    V4L2,
+}
+impl From<Interface> for i32 {
+    fn from(interface: Interface) -> Self {
+        match interface {
+            Interface::Auto => 0,
+            Interface::V4L1 => 1,
+            Interface::V4L2 => 2,
+        }
+    }
+}
+
+/// The video I/O mode requested by `ZBarProcessor::request_iomode_typed`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum IoMode {
+    /// Let ZBar choose the best available mode.
+    Auto,
+    /// Read frames with `read()`.
+    Read,
+    /// Capture into memory mapped kernel buffers.
+    MemoryMapped,
+    /// Capture into user space buffers.
+    UserSpace,
+}
+impl From<IoMode> for i32 {
+    fn from(iomode: IoMode) -> Self {
+        match iomode {
+            IoMode::Auto         => 0,
+            IoMode::Read         => 1,
+            IoMode::MemoryMapped => 2,
+            IoMode::UserSpace    => 3,
+        }
+    }
+}
+
+/// Snapshot of the UI state of a `ZBarProcessor` as returned by `ZBarProcessor::state`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ProcessorState {
+    pub visible: bool,
+    pub active: bool,
+}
+
+/// Future returned by `ZBarProcessor::process_one_async`.
+#[cfg(feature = "async")]
+pub struct ProcessOne {
+    processor: Arc<ZBarProcessor>,
+    timeout: i32,
+    /// The blocking task, spawned on the first poll.
+    handle: Option<JoinHandle<i32>>,
+}
+#[cfg(feature = "async")]
+impl Future for ProcessOne {
+    type Output = ZBarResult<Option<ZBarSymbolSet>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        if self.handle.is_none() {
+            let (processor, timeout) = (self.processor.clone(), self.timeout);
+            self.handle = Some(task::spawn_blocking(move || unsafe {
+                ffi::zbar_process_one(processor.processor, timeout)
+            }));
+        }
+        // the handle has just been set if it was missing
+        let result = Pin::new(self.handle.as_mut().unwrap()).poll(cx);
+        match result {
+            Poll::Pending        => Poll::Pending,
+            Poll::Ready(Ok(r))   => Poll::Ready(self.processor.process_one_results(r)),
+            Poll::Ready(Err(_))  => Poll::Ready(
+                Err(ZBarErrorType::Complex(ZBarError::ZBAR_ERR_INTERNAL))
+            ),
+        }
+    }
+}
+
+// ZBar locks the processor in every function but the config setters, which take `&mut self`.
 unsafe impl Send for ZBarProcessor {}
 unsafe impl Sync for ZBarProcessor {}
 
+impl fmt::Debug for ZBarProcessor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ZBarProcessor").field("processor", &self.processor).finish()
+    }
+}
+
 impl Drop for ZBarProcessor {
     fn drop(&mut self) { unsafe { ffi::zbar_processor_destroy(self.processor) } }
 }
 
+/// Builder for `ZBarProcessor`.
 #[derive(Default)]
 pub struct ZBarProcessorBuilder {
     threaded: bool,
+    dedup_results: bool,
     size: Option<(u32, u32)>,
     interface_version: Option<i32>,
     iomode: Option<i32>,
@@ -206,6 +872,7 @@ impl ZBarProcessorBuilder {
     pub fn new() -> Self {
         Self {
             threaded: false,
+            dedup_results: false,
             size: None,
             interface_version: None,
             iomode: None,
@@ -214,11 +881,24 @@ impl ZBarProcessorBuilder {
         }
     }
     pub fn threaded(&mut self, threaded: bool) -> &mut Self { self.threaded = threaded; self }
+    /// Enables deduplication of the results sent to `ZBarProcessor::results_channel`, disabled
+    /// by default.
+    ///
+    /// See `ZBarProcessor::dedup_results`.
+    pub fn with_dedup_results(&mut self, dedup_results: bool) -> &mut Self {
+        self.dedup_results = dedup_results; self
+    }
     pub fn with_size(&mut self, size: Option<(u32, u32)>) -> &mut Self { self.size = size; self }
     pub fn with_interface_version(&mut self, interface_version: Option<i32>) -> &mut Self {
         self.interface_version = interface_version; self
     }
     pub fn with_iomode(&mut self, iomode: Option<i32>) -> &mut Self { self.iomode = iomode; self }
+    pub fn with_interface(&mut self, interface: Interface) -> &mut Self {
+        self.interface_version = Some(interface.into()); self
+    }
+    pub fn with_iomode_typed(&mut self, iomode: IoMode) -> &mut Self {
+        self.iomode = Some(iomode.into()); self
+    }
     pub fn with_format(&mut self, format: Option<(Format, Format)>) -> &mut Self {
         self.format = format; self
     }
@@ -233,6 +913,7 @@ impl ZBarProcessorBuilder {
     }
     pub fn build(&self) -> ZBarResult<ZBarProcessor> {
         let mut processor = ZBarProcessor::new(self.threaded);
+        processor.dedup_results(self.dedup_results);
         if let Some(size) = self.size {
             processor.request_size(size.0, size.1)?;
         }
@@ -266,6 +947,270 @@ mod test {
         assert!(processor.init("nothing", true).is_err())
     }
 
+    #[test]
+    fn test_control_name_nul() {
+        let processor = ZBarProcessor::new(false);
+        match processor.set_control("bright\0ness", 75) {
+            Err(ZBarErrorType::Complex(ZBarError::ZBAR_ERR_INVALID)) => {},
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(processor.init("/dev/video\00", false).is_err());
+    }
+
+    #[test]
+    fn test_last_error() {
+        let processor = ZBarProcessor::builder()
+            .threaded(true)
+            .build()
+            .unwrap();
+
+        assert!(processor.init("nothing", false).is_err());
+        assert_ne!(processor.last_error(), ZBarError::ZBAR_OK);
+        assert!(!processor.last_error_string().is_empty());
+    }
+
+    #[test]
+    fn test_init_detailed() {
+        let processor = ZBarProcessor::builder()
+            .threaded(true)
+            .build()
+            .unwrap();
+
+        match processor.init_detailed("nonexistent", false) {
+            Err(ProcessorInitError::DeviceNotFound) | Err(ProcessorInitError::Other(_)) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_init_error() {
+        assert_eq!(
+            init_error(ZBarError::ZBAR_ERR_SYSTEM, "/nowhere/video0"),
+            ProcessorInitError::DeviceNotFound
+        );
+        assert_eq!(init_error(ZBarError::ZBAR_ERR_BUSY, "/dev/video0"), ProcessorInitError::Busy);
+        assert_eq!(
+            init_error(ZBarError::ZBAR_ERR_UNSUPPORTED, "/dev/video0"),
+            ProcessorInitError::Unsupported
+        );
+        assert_eq!(
+            init_error(ZBarError::ZBAR_ERR_INTERNAL, "/dev/video0"),
+            ProcessorInitError::Other(ZBarError::ZBAR_ERR_INTERNAL as i32)
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_is_busy() {
+        assert!(is_busy(&io::Error::from_raw_os_error(16)));
+        assert!(!is_busy(&io::Error::from(ErrorKind::NotFound)));
+    }
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ZBarProcessor>();
+    }
+
+    #[test]
+    #[cfg(feature = "from_image")]
+    fn test_last_frame_size() {
+        let processor = ZBarProcessor::builder()
+            .with_config(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .build()
+            .unwrap();
+        assert!(processor.last_frame_size().is_none());
+
+        // no symbols, so the data handler isn't invoked
+        let blank = ZBarImage::new(4, 3, ::format::Y800, vec![255; 4 * 3]).unwrap();
+        processor.process_image(&blank).unwrap();
+        assert!(processor.last_frame_size().is_none());
+
+        processor.process_image(&ZBarImage::from_path("test/qr_hello-world.png").unwrap())
+            .unwrap();
+        assert_eq!(processor.last_frame_size(), Some((150, 150)));
+    }
+
+    #[test]
+    #[ignore] // requires a video device showing a barcode
+    fn test_last_frame_size_video() {
+        let processor = ZBarProcessor::builder()
+            .threaded(true)
+            .with_size(Some((640, 480)))
+            .with_config(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .build()
+            .unwrap();
+
+        processor.init("/dev/video0", false).unwrap();
+        while processor.process_one(1000).unwrap().is_none() {}
+        assert!(processor.last_frame_size().is_some());
+    }
+
+    #[test]
+    fn test_results_channel_closes_on_drop() {
+        let mut processor = ZBarProcessor::new(false);
+        let results = processor.results_channel();
+        drop(processor);
+        assert!(results.recv().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "from_image")]
+    fn test_results_channel() {
+        let image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+
+        let mut processor = ZBarProcessor::builder()
+            .with_config(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .build()
+            .unwrap();
+        let results = processor.results_channel();
+        processor.process_image(&image).unwrap();
+
+        let symbols = results.try_recv().unwrap();
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].data(), "Hello World");
+    }
+
+    #[test]
+    #[cfg(feature = "from_image")]
+    fn test_results_channel_binary() {
+        let image = ZBarImage::from_path("test/code128_nul.png").unwrap();
+
+        let mut processor = ZBarProcessor::builder()
+            .with_config(ZBarSymbolType::ZBAR_CODE128, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .build()
+            .unwrap();
+        let results = processor.results_channel();
+        processor.process_image(&image).unwrap();
+
+        let symbols = results.try_recv().unwrap();
+        assert_eq!(symbols[0].data_bytes(), b"AB\0CD");
+    }
+
+    #[test]
+    #[cfg(feature = "from_image")]
+    fn test_results_channel_dedup() {
+        let image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+
+        let mut processor = ZBarProcessor::builder()
+            .with_dedup_results(true)
+            .with_config(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .build()
+            .unwrap();
+        let results = processor.results_channel();
+        processor.process_image(&image).unwrap();
+        processor.process_image(&image).unwrap();
+        assert_eq!(results.try_recv().unwrap().len(), 1);
+        assert!(results.try_recv().unwrap().is_empty());
+
+        processor.dedup_results(false);
+        processor.process_image(&image).unwrap();
+        processor.process_image(&image).unwrap();
+        assert_eq!(results.try_recv().unwrap().len(), 1);
+        assert_eq!(results.try_recv().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_state_without_video() {
+        let processor = ZBarProcessor::new(false);
+        assert_eq!(processor.state().unwrap(), ProcessorState { visible: false, active: false });
+
+        // fails without video, so the processor remains inactive
+        assert!(processor.set_active(true).is_err());
+        assert!(!processor.state().unwrap().active);
+    }
+
+    #[test]
+    #[ignore] // requires a video device
+    fn test_state() {
+        let processor = ZBarProcessor::builder().threaded(true).build().unwrap();
+        processor.init("/dev/video0", false).unwrap();
+
+        processor.set_active(true).unwrap();
+        assert!(processor.state().unwrap().active);
+        processor.set_active(false).unwrap();
+        assert!(!processor.state().unwrap().active);
+    }
+
+    #[test]
+    fn test_retry_until() {
+        let mut attempts = 0;
+        let result = retry_until(3, || { attempts += 1; Ok(None::<u32>) }, |_| true);
+        assert_eq!(result.unwrap(), None);
+        assert_eq!(attempts, 3);
+
+        let mut attempts = 0;
+        let result = retry_until(5, || { attempts += 1; Ok(Some(attempts)) }, |n| *n == 2);
+        assert_eq!(result.unwrap(), Some(2));
+        assert_eq!(attempts, 2);
+
+        let mut attempts = 0;
+        let result = retry_until(
+            5,
+            || { attempts += 1; Err::<Option<u32>, _>(ZBarErrorType::Simple(-1)) },
+            |_| true
+        );
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn test_process_until_without_video() {
+        let processor = ZBarProcessor::new(false);
+        assert!(processor.process_until(0, 0, |_| true).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_user_event() {
+        assert_eq!(user_event(0, ZBarError::ZBAR_OK).unwrap(), UserEvent::Timeout);
+        assert_eq!(user_event(1, ZBarError::ZBAR_OK).unwrap(), UserEvent::MouseClick(1));
+        assert_eq!(user_event(3, ZBarError::ZBAR_OK).unwrap(), UserEvent::MouseClick(3));
+        assert_eq!(
+            user_event(i32::from(b'q'), ZBarError::ZBAR_OK).unwrap(),
+            UserEvent::KeyPress(i32::from(b'q'))
+        );
+        assert_eq!(
+            user_event(-1, ZBarError::ZBAR_ERR_CLOSED).unwrap(),
+            UserEvent::WindowClosed
+        );
+        assert!(user_event(-1, ZBarError::ZBAR_ERR_SYSTEM).is_err());
+    }
+
+    #[test]
+    fn test_timeout_millis() {
+        assert_eq!(timeout_millis(None).unwrap(), -1);
+        assert_eq!(timeout_millis(Some(Duration::from_millis(0))).unwrap(), 0);
+        assert_eq!(timeout_millis(Some(Duration::from_millis(1500))).unwrap(), 1500);
+        assert!(timeout_millis(Some(Duration::from_secs(u64::max_value()))).is_err());
+    }
+
+    #[test]
+    fn test_interface_iomode_values() {
+        assert_eq!(i32::from(Interface::Auto), 0);
+        assert_eq!(i32::from(Interface::V4L1), 1);
+        assert_eq!(i32::from(Interface::V4L2), 2);
+        assert_eq!(i32::from(IoMode::Auto), 0);
+        assert_eq!(i32::from(IoMode::Read), 1);
+        assert_eq!(i32::from(IoMode::MemoryMapped), 2);
+        assert_eq!(i32::from(IoMode::UserSpace), 3);
+    }
+
+    #[test]
+    fn test_build_with_interface_iomode() {
+        let processor = ZBarProcessor::builder()
+            .with_interface(Interface::V4L2)
+            .with_iomode_typed(IoMode::MemoryMapped)
+            .build()
+            .unwrap();
+        assert!(processor.request_interface_typed(Interface::Auto).is_ok());
+        assert!(processor.request_iomode_typed(IoMode::Auto).is_ok());
+    }
+
+    #[test]
+    fn test_debug() {
+        assert!(format!("{:?}", ZBarProcessor::new(false)).starts_with("ZBarProcessor"));
+    }
+
     #[test]
     #[cfg(feature = "from_image")]
     fn test_process_image() {
@@ -287,6 +1232,89 @@ mod test {
         assert_eq!(symbol.next().is_none(), true);
     }
 
+    #[test]
+    #[cfg(feature = "from_image")]
+    fn test_process_image_non_clone_data() {
+        // deliberately doesn't implement Clone
+        struct Data(Vec<u8>);
+        impl AsRef<[u8]> for Data {
+            fn as_ref(&self) -> &[u8] { &self.0 }
+        }
+
+        let luma = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+        let image = ZBarImage::new(
+            luma.width(), luma.height(), luma.format(), Data(luma.data().to_vec())
+        ).unwrap();
+
+        let processor = ZBarProcessor::builder()
+            .threaded(true)
+            .with_config(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .build()
+            .unwrap();
+
+        let symbols = processor.process_image(&image).unwrap();
+        assert_eq!(symbols.first_symbol().unwrap().data(), "Hello World");
+    }
+
+    #[test]
+    #[cfg(feature = "from_image")]
+    fn test_process_image_created_before_processor() {
+        let image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+
+        let processor = ZBarProcessor::builder()
+            .with_config(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .build()
+            .unwrap();
+
+        let symbols = processor.process_image(&image).unwrap();
+        assert_eq!(symbols.first_symbol().unwrap().data(), "Hello World");
+    }
+
+    #[test]
+    #[ignore] // requires a video device
+    #[cfg(feature = "from_image")]
+    fn test_process_image_created_before_init() {
+        let image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+
+        let processor = ZBarProcessor::builder()
+            .threaded(true)
+            .with_config(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .build()
+            .unwrap();
+        processor.init("/dev/video0", false).unwrap();
+
+        let symbols = processor.process_image(&image).unwrap();
+        assert_eq!(symbols.first_symbol().unwrap().data(), "Hello World");
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn test_process_one_async() {
+        let processor = Arc::new(ZBarProcessor::builder().threaded(true).build().unwrap());
+
+        // created outside of the runtime, the blocking task is spawned once block_on polls it
+        let future = processor.process_one_async(1);
+        let result = ::tokio::runtime::Runtime::new().unwrap().block_on(future);
+
+        // Video has not been initialized, so there is either an error or nothing to report.
+        assert!(result.map(|symbols| symbols.is_none()).unwrap_or(true));
+    }
+
+    #[test]
+    #[cfg(feature = "from_image")]
+    fn test_process_image_unthreaded() {
+        let image = ZBarImage::from_path("test/qr_hello-world.png").unwrap();
+
+        let mut processor = ZBarProcessor::new_unthreaded_polling();
+        processor
+            .set_config(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .unwrap();
+
+        let symbols = processor.process_image(&image).unwrap();
+
+        assert_eq!(symbols.first_symbol().unwrap().data(), "Hello World");
+    }
+
     #[test]
     #[cfg(feature = "zbar_fork")]
     fn test_control_get_set() {
@@ -5,14 +5,72 @@ use {
     symbol_set::ZBarSymbolSet,
     ZBarSymbolType
 };
-use std::ffi::CString;
+use std::{
+    borrow::Cow,
+    ffi::CString,
+    fmt,
+    hash::{
+        Hash,
+        Hasher
+    },
+    slice::from_raw_parts,
+    str::{
+        self,
+        Utf8Error
+    },
+};
+#[cfg(feature = "zbar_fork")]
+use {
+    ZBarModifier,
+    ZBarOrientation
+};
+
 #[cfg(feature = "zbar_fork")]
-use ZBarOrientation;
+const MODIFIERS: [ZBarModifier; 2] = [ZBarModifier::ZBAR_MOD_GS1, ZBarModifier::ZBAR_MOD_AIM];
 
+/// Common GS1 Application Identifiers as `(prefix, AI length, fixed value length)`.
+///
+/// Values without a fixed length are terminated by a group separator or the end of the data.
+#[cfg(feature = "zbar_fork")]
+const GS1_AIS: &[(&str, usize, Option<usize>)] = &[
+    ("00", 2, Some(18)), ("01", 2, Some(14)), ("02", 2, Some(14)), ("10", 2, None),
+    ("11", 2, Some(6)), ("12", 2, Some(6)), ("13", 2, Some(6)), ("15", 2, Some(6)),
+    ("16", 2, Some(6)), ("17", 2, Some(6)), ("20", 2, Some(2)), ("21", 2, None),
+    ("22", 2, None), ("240", 3, None), ("241", 3, None), ("242", 3, None), ("250", 3, None),
+    ("251", 3, None), ("253", 3, None), ("254", 3, None), ("30", 2, None),
+    ("31", 4, Some(6)), ("32", 4, Some(6)), ("33", 4, Some(6)), ("34", 4, Some(6)),
+    ("35", 4, Some(6)), ("36", 4, Some(6)), ("37", 2, None), ("400", 3, None),
+    ("401", 3, None), ("402", 3, Some(17)), ("403", 3, None), ("41", 3, Some(13)),
+    ("420", 3, None), ("421", 3, None), ("422", 3, Some(3)), ("7003", 4, Some(10)),
+    ("8004", 4, None), ("8005", 4, Some(6)), ("8020", 4, None), ("9", 2, None),
+];
+
+/// Byte mode capacity of QR code versions 1 to 40 at error correction level L.
+const QR_BYTE_CAPACITIES: [usize; 40] = [
+    17, 32, 53, 78, 106, 134, 154, 192, 230, 271, 321, 367, 425, 458, 520, 586, 644, 718, 792,
+    858, 929, 1003, 1091, 1171, 1273, 1367, 1465, 1528, 1628, 1732, 1840, 1952, 2068, 2188,
+    2303, 2431, 2563, 2699, 2809, 2953,
+];
+
+/// The group separator terminating variable length GS1 fields (FNC1 in the symbol).
+#[cfg(feature = "zbar_fork")]
+const GS: char = '\u{1d}';
+
+
+/// The position of a part in a QR code structured append sequence.
+#[derive(Clone, Copy)]
+struct Part {
+    index: u8,
+    total: u8,
+    // the composite ZBar grouped the part into, standing in for the parity ZBar doesn't expose
+    sequence: *const ffi::zbar_symbol_s,
+}
 
 pub struct ZBarSymbol {
     symbol: *const ffi::zbar_symbol_s,
-    image: *mut ffi::zbar_image_s
+    image: *mut ffi::zbar_image_s,
+    // only known for parts of a composite
+    part: Option<Part>,
 }
 impl ZBarSymbol {
     /// Creates a new `SymbolSet` from raw data.
@@ -21,7 +79,7 @@ impl ZBarSymbol {
         image: *mut ffi::zbar_image_s) -> Option<Self>
     {
         if !symbol.is_null() {
-            let symbol = Self { symbol, image };
+            let symbol = Self { symbol, image, part: None };
             image::set_ref(image, 1);
             Some(symbol)
         } else {
@@ -32,6 +90,10 @@ impl ZBarSymbol {
     pub fn symbol_type(&self) -> ZBarSymbolType {
         unsafe { ffi::zbar_symbol_get_type(self.symbol) }
     }
+    /// Returns ZBar's numeric value of the symbol type, e.g. for serialization.
+    ///
+    /// Use `symbol_type_from_value` to restore the `ZBarSymbolType`.
+    pub fn symbol_type_value(&self) -> i32 { self.symbol_type() as i32 }
 
     /// Returns the decoded data for this `Symbol`
     ///
@@ -49,6 +111,31 @@ impl ZBarSymbol {
     /// };
     /// ```
     pub fn data(&self) -> &str { unsafe { from_cstr(ffi::zbar_symbol_get_data(self.symbol)) } }
+    /// Returns the length of the decoded data in bytes.
+    ///
+    /// Binary data may contain NUL bytes which truncate `data`, so use this together with
+    /// `data_bytes` to handle binary payloads.
+    pub fn data_len(&self) -> usize {
+        unsafe { ffi::zbar_symbol_get_data_length(self.symbol) as usize }
+    }
+    /// Returns the raw decoded data for this `Symbol`.
+    ///
+    /// Unlike `data` this doesn't require the data to be valid UTF-8 and isn't truncated at
+    /// NUL bytes.
+    pub fn data_bytes(&self) -> &[u8] {
+        unsafe {
+            from_raw_parts(ffi::zbar_symbol_get_data(self.symbol) as *const u8, self.data_len())
+        }
+    }
+    /// Returns the decoded data for this `Symbol` replacing invalid UTF-8 sequences with
+    /// `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// Unlike `data` this never panics. No allocation takes place if the data is valid UTF-8.
+    pub fn data_cow(&self) -> Cow<str> { lossy_text(self.data_bytes()) }
+    /// Returns the decoded data for this `Symbol` handled according to the given `TextPolicy`.
+    pub fn data_with(&self, policy: TextPolicy) -> Result<SymbolData, Utf8Error> {
+        policy.apply(self.data_bytes())
+    }
     pub fn quality(&self) -> i32 { unsafe { ffi::zbar_symbol_get_quality(self.symbol) } }
     /// Retrieve the current cache count
     pub fn count(&self) -> i32 {
@@ -73,18 +160,169 @@ impl ZBarSymbol {
             y  => Some(y as u32),
         }
     }
+    /// Returns the raw signed coordinates of the location point at `index`.
+    ///
+    /// Unlike `loc_x` and `loc_y` a coordinate of `-1` isn't mistaken for a missing point.
+    /// ZBar may report negative coordinates for symbols extending beyond the image. Returns
+    /// `None` only if `index` is out of range.
+    pub fn loc_raw(&self, index: u32) -> Option<(i32, i32)> {
+        if index < self.loc_size() {
+            unsafe {
+                Some((
+                    ffi::zbar_symbol_get_loc_x(self.symbol, index),
+                    ffi::zbar_symbol_get_loc_y(self.symbol, index)
+                ))
+            }
+        } else {
+            None
+        }
+    }
     fn loc(&self, index: u32) -> Option<(u32, u32)> {
         self.loc_x(index).map(|x| (x, self.loc_y(index).unwrap()))
     }
+    /// Iterates over the location points without cloning the symbol.
+    ///
+    /// Yields the same points as `polygon().iter()` but borrows `self` instead of bumping the
+    /// reference count of the underlying image.
+    pub fn points_iter<'a>(&'a self) -> impl Iterator<Item = (u32, u32)> + 'a {
+        (0..self.loc_size()).filter_map(move |index| self.loc(index))
+    }
+    /// Returns the axis-aligned bounding box of the location points as
+    /// `(x, y, width, height)` or `None` if there is no location data.
+    pub fn bounding_box(&self) -> Option<(u32, u32, u32, u32)> {
+        self.points_iter()
+            .fold(None, |bounds, (x, y)| match bounds {
+                None                               => Some((x, y, x, y)),
+                Some((min_x, min_y, max_x, max_y)) => Some((
+                    min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y)
+                )),
+            })
+            .map(|(min_x, min_y, max_x, max_y)| (min_x, min_y, max_x - min_x, max_y - min_y))
+    }
+    /// Estimates the size of a single module of a QR code in pixels.
+    ///
+    /// ZBar doesn't report the QR version, so the smallest version able to hold the data in
+    /// byte mode at the lowest error correction level is assumed. Codes using a larger version
+    /// have smaller modules, so the result is an upper bound. Returns `None` for all other
+    /// symbologies and if there is no location data.
+    pub fn estimated_module_size(&self) -> Option<f64> {
+        if self.symbol_type() != ZBarSymbolType::ZBAR_QRCODE {
+            return None;
+        }
+        let version = QR_BYTE_CAPACITIES
+            .iter()
+            .position(|capacity| *capacity >= self.data_len())? + 1;
+        let modules = 17 + 4 * version;
+        self.bounding_box().map(|(_, _, width, height)| {
+            f64::from(width.max(height)) / modules as f64
+        })
+    }
     pub fn next(&self) -> Option<Self> {
         Self::from_raw(unsafe { ffi::zbar_symbol_next(self.symbol) }, self.image)
     }
+    /// Returns the components if this is a composite `Symbol`.
+    ///
+    /// ZBar reports EAN/UPC codes with an add-on and QR codes using structured append as
+    /// composite symbols. The parts of a structured append sequence found in the same image are
+    /// reassembled by ZBar: the data of the composite is the concatenated payload and its
+    /// components are the single parts in sequence order (see `structured_append_parts`).
     pub fn components(&self) -> Option<ZBarSymbolSet> {
         ZBarSymbolSet::from_raw(unsafe { ffi::zbar_symbol_get_components(self.symbol) }, self.image)
     }
     pub fn first_component(&self) -> Option<Self> {
         Self::from_raw(unsafe { ffi::zbar_symbol_first_component(self.symbol) }, self.image)
     }
+    /// Returns the parts of a QR code structured append sequence with their
+    /// `structured_append_info` set.
+    ///
+    /// ZBar groups the parts found in one image into a composite `ZBAR_QRCODE` symbol and fills
+    /// the gaps of missing parts with `ZBAR_PARTIAL` placeholders, so the position of a
+    /// component is its index in the sequence. It only groups parts with the same parity, i.e.
+    /// of the same sequence, so every composite stands for one sequence. Returns `None` for all
+    /// other symbols.
+    pub fn structured_append_parts(&self) -> Option<Vec<Self>> {
+        match self.symbol_type() {
+            ZBarSymbolType::ZBAR_QRCODE | ZBarSymbolType::ZBAR_PARTIAL => {}
+            _                                                          => return None,
+        }
+        let parts = self.components()?.iter().collect::<Vec<_>>();
+        let total = parts.len() as u8;
+        Some(
+            parts
+                .into_iter()
+                .enumerate()
+                .map(|(index, mut part)| {
+                    part.part = Some(Part { index: index as u8, total, sequence: self.symbol });
+                    part
+                })
+                .collect()
+        )
+    }
+    /// Returns the index of this part and the total number of parts of a QR code structured
+    /// append sequence.
+    ///
+    /// ZBar only reports the sequence through the composite symbol, so this is `None` unless
+    /// the symbol was returned by `structured_append_parts`.
+    pub fn structured_append_info(&self) -> Option<(u8, u8)> {
+        self.part.map(|part| (part.index, part.total))
+    }
+    /// Recomputes the check digit of EAN-13, EAN-8 and UPC-A symbols and compares it with the
+    /// decoded one.
+    ///
+    /// Returns `None` for all other symbologies. The check digit has to be part of the decoded
+    /// data, which is the default (see `ZBAR_CFG_EMIT_CHECK`).
+    pub fn validate_checksum(&self) -> Option<bool> {
+        match self.symbol_type() {
+            ZBarSymbolType::ZBAR_EAN13
+            | ZBarSymbolType::ZBAR_EAN8
+            | ZBarSymbolType::ZBAR_UPCA => Some(is_gtin_checksum_valid(self.data())),
+            _ => None,
+        }
+    }
+    /// Returns the decoded data of EAN-13, EAN-8, UPC-A and UPC-E symbols as 14 digit GTIN.
+    ///
+    /// Shorter codes are padded with leading zeros, UPC-E is expanded to UPC-A first. Returns
+    /// `None` for all other symbologies and if the check digit isn't part of the decoded data
+    /// (see `ZBAR_CFG_EMIT_CHECK`).
+    pub fn gtin14(&self) -> Option<String> {
+        let data = self.data();
+        let expected_len = match self.symbol_type() {
+            ZBarSymbolType::ZBAR_EAN13 => 13,
+            ZBarSymbolType::ZBAR_UPCA  => 12,
+            ZBarSymbolType::ZBAR_EAN8 | ZBarSymbolType::ZBAR_UPCE => 8,
+            _ => return None,
+        };
+        if data.len() != expected_len || !data.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let gtin = match self.symbol_type() {
+            ZBarSymbolType::ZBAR_UPCE => expand_upce(data),
+            _                         => data.to_owned(),
+        };
+        Some(format!("{:0>14}", gtin))
+    }
+    /// Returns `true` if this `Symbol` is an EAN/UPC add-on (2 or 5 digit supplement).
+    pub fn is_addon(&self) -> bool {
+        match self.symbol_type() {
+            ZBarSymbolType::ZBAR_ADDON2
+            | ZBarSymbolType::ZBAR_ADDON5
+            | ZBarSymbolType::ZBAR_ADDON => true,
+            #[cfg(feature = "zbar_fork")]
+            ZBarSymbolType::ZBAR_EAN2
+            | ZBarSymbolType::ZBAR_EAN5 => true,
+            _ => false,
+        }
+    }
+    /// Returns the data of the add-on if this is a composite `Symbol` containing one.
+    ///
+    /// ZBar reports an EAN/UPC code followed by a supplement as a single composite symbol whose
+    /// components are the main code and the add-on. Add-on decoding has to be enabled, e.g. via
+    /// `ZBAR_EAN5` on the fork build.
+    pub fn addon_data(&self) -> Option<&str> {
+        self.components()
+            .and_then(|components| components.iter().find(ZBarSymbol::is_addon))
+            .map(|addon| unsafe { from_cstr(ffi::zbar_symbol_get_data(addon.symbol)) })
+    }
     /// Returns a xml representation of the `Symbol`.
     pub fn xml(&self) -> &str {
         let cstr_buf = CString::new("").unwrap();
@@ -106,24 +344,279 @@ impl ZBarSymbol {
 impl ZBarSymbol {
     pub fn configs(&self) -> u32 { unsafe { ffi::zbar_symbol_get_configs(self.symbol) } }
     pub fn modifiers(&self) -> u32 { unsafe { ffi::zbar_symbol_get_modifiers(self.symbol) } }
+    /// Returns whether the given modifier is set in the `modifiers` bitmask.
+    pub fn has_modifier(&self, modifier: ZBarModifier) -> bool {
+        self.modifiers() & (1 << modifier as u32) != 0
+    }
+    /// Returns all modifiers that are set in the `modifiers` bitmask.
+    pub fn modifier_list(&self) -> Vec<ZBarModifier> {
+        MODIFIERS.iter().cloned().filter(|modifier| self.has_modifier(*modifier)).collect()
+    }
     pub fn orientation(&self) -> ZBarOrientation {
         unsafe { ffi::zbar_symbol_get_orientation (self.symbol) }
     }
+    /// Returns the orientation as the feature-independent `Orientation`.
+    pub fn orientation_kind(&self) -> Orientation { self.orientation().into() }
+    /// Splits the data of a GS1 symbol (e.g. GS1-128) into `(AI, value)` pairs.
+    ///
+    /// Returns `None` if the `ZBAR_MOD_GS1` modifier isn't set or the data contains an
+    /// Application Identifier that isn't known.
+    pub fn parse_gs1(&self) -> Option<Vec<(String, String)>> {
+        if self.has_modifier(ZBarModifier::ZBAR_MOD_GS1) {
+            parse_gs1_fields(self.data())
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(feature = "zbar_fork")]
+fn parse_gs1_fields(mut data: &str) -> Option<Vec<(String, String)>> {
+    let mut fields = vec![];
+    while !data.is_empty() {
+        let &(_, ai_len, value_len) = GS1_AIS.iter().find(|ai| data.starts_with(ai.0))?;
+        if data.len() < ai_len || !data.as_bytes()[..ai_len].iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        let (ai, rest) = data.split_at(ai_len);
+        let value_len = match value_len {
+            Some(len) if rest.is_char_boundary(len) => len,
+            Some(_)                                 => return None,
+            None                                    => rest.find(GS).unwrap_or(rest.len()),
+        };
+        let (value, rest) = rest.split_at(value_len);
+        fields.push((ai.to_owned(), value.to_owned()));
+        data = rest.trim_start_matches(GS);
+    }
+    if fields.is_empty() { None } else { Some(fields) }
+}
+
+/// Expands 8 digit UPC-E data (number system, 6 digits, check digit) to 12 digit UPC-A.
+fn expand_upce(data: &str) -> String {
+    let (number_system, d, check) = (&data[..1], data[1..7].as_bytes(), &data[7..]);
+    let digits = |range: &[usize]| range.iter().map(|i| d[*i] as char).collect::<String>();
+    let (manufacturer, product) = match d[5] {
+        b'0'..=b'2' => (digits(&[0, 1, 5]) + "00", "00".to_owned() + &digits(&[2, 3, 4])),
+        b'3'        => (digits(&[0, 1, 2]) + "00", "000".to_owned() + &digits(&[3, 4])),
+        b'4'        => (digits(&[0, 1, 2, 3]) + "0", "0000".to_owned() + &digits(&[4])),
+        _           => (digits(&[0, 1, 2, 3, 4]), "0000".to_owned() + &digits(&[5])),
+    };
+    format!("{}{}{}{}", number_system, manufacturer, product, check)
+}
+
+/// Checks the trailing GS1 check digit of a numeric string.
+fn is_gtin_checksum_valid(data: &str) -> bool {
+    match data.chars().map(|c| c.to_digit(10)).collect::<Option<Vec<_>>>() {
+        Some(ref digits) if digits.len() > 1 => {
+            let (check, payload) = digits.split_last().unwrap(); // safe to unwrap here
+            let sum = payload
+                .iter()
+                .rev()
+                .enumerate()
+                .map(|(i, digit)| if i % 2 == 0 { digit * 3 } else { *digit })
+                .sum::<u32>();
+            (10 - sum % 10) % 10 == *check
+        }
+        _ => false,
+    }
 }
 
 impl Clone for ZBarSymbol {
-    fn clone(&self) -> Self { Self::from_raw(self.symbol, self.image).unwrap() }
+    fn clone(&self) -> Self {
+        let mut symbol = Self::from_raw(self.symbol, self.image).unwrap();
+        symbol.part = self.part;
+        symbol
+    }
+}
+impl fmt::Debug for ZBarSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ZBarSymbol")
+            .field("symbol_type", &self.symbol_type())
+            .field("data", &self.data_cow())
+            .field("quality", &self.quality())
+            .finish()
+    }
+}
+/// Symbols are compared by their type and decoded data only.
+///
+/// Their position within the image is ignored, so two symbols decoded from different images
+/// or locations are equal as long as they carry the same data. This allows deduplicating symbols
+/// in a `HashSet`.
+impl PartialEq for ZBarSymbol {
+    fn eq(&self, other: &Self) -> bool {
+        self.symbol_type() == other.symbol_type() && self.data_bytes() == other.data_bytes()
+    }
+}
+impl Eq for ZBarSymbol {}
+impl Hash for ZBarSymbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.symbol_type().hash(state);
+        self.data_bytes().hash(state);
+    }
 }
 impl Drop for ZBarSymbol {
     fn drop(&mut self) { image::set_ref(self.image, -1) }
 }
 
+/// Determines how decoded data that is not valid UTF-8 is handled.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TextPolicy {
+    /// Invalid UTF-8 results in an error.
+    Strict,
+    /// Invalid UTF-8 sequences are replaced with `U+FFFD REPLACEMENT CHARACTER`.
+    Lossy,
+    /// The raw bytes are returned without any conversion.
+    Bytes,
+}
+impl TextPolicy {
+    /// Applies this policy to the given decoded data.
+    pub fn apply<'a>(&self, bytes: &'a [u8]) -> Result<SymbolData<'a>, Utf8Error> {
+        match *self {
+            TextPolicy::Strict => str::from_utf8(bytes).map(|s| SymbolData::Text(s.into())),
+            TextPolicy::Lossy  => Ok(SymbolData::Text(lossy_text(bytes))),
+            TextPolicy::Bytes  => Ok(SymbolData::Bytes(bytes)),
+        }
+    }
+}
+impl Default for TextPolicy {
+    fn default() -> Self { TextPolicy::Strict }
+}
+
+/// Concatenates the data of the parts of a QR code structured append sequence in order.
+///
+/// The parts have to be obtained by `ZBarSymbol::structured_append_parts` of the same composite
+/// symbol. ZBar doesn't expose the parity identifying a sequence, so parts of different
+/// composites are never joined, even if they have the same length. `ZBAR_PARTIAL` placeholders
+/// are ignored. Returns `None` if a part is missing or duplicated, the parts belong to
+/// different sequences or the data is not valid UTF-8.
+///
+/// # Examples
+///
+/// ```
+/// use zbars::prelude::*;
+/// use zbars::symbol::reassemble;
+///
+/// let image = ZBarImage::from_path("test/qr_structured_append.png").unwrap();
+/// let scanner = ZBarImageScanner::builder()
+///     .with_config(ZBarSymbolType::ZBAR_QRCODE, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+///     .build()
+///     .unwrap();
+/// let symbol = scanner.scan_image(&image).unwrap().first_symbol().unwrap();
+/// let parts = symbol.structured_append_parts().unwrap();
+/// assert_eq!(reassemble(&parts).unwrap(), "Hello World");
+/// ```
+pub fn reassemble(symbols: &[ZBarSymbol]) -> Option<String> {
+    let mut parts = symbols
+        .iter()
+        .filter(|symbol| symbol.symbol_type() != ZBarSymbolType::ZBAR_PARTIAL)
+        .map(|symbol| symbol.part.map(|part| (part, symbol)))
+        .collect::<Option<Vec<_>>>()?;
+    parts.sort_by_key(|&(part, _)| part.index);
+    let first = parts.first()?.0;
+    let complete = parts.len() == first.total as usize
+        && parts.iter().enumerate().all(|(i, &(part, _))| {
+            part.index as usize == i
+                && part.total == first.total
+                && part.sequence == first.sequence
+        });
+    if !complete {
+        return None;
+    }
+    let data = parts.iter().flat_map(|&(_, symbol)| symbol.data_bytes().iter().cloned()).collect();
+    String::from_utf8(data).ok()
+}
+
+/// Borrows valid UTF-8 and only allocates if invalid sequences have to be replaced.
+fn lossy_text(bytes: &[u8]) -> Cow<str> { String::from_utf8_lossy(bytes) }
+
+/// Decoded data of a `Symbol` as produced by a `TextPolicy`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SymbolData<'a> {
+    Text(Cow<'a, str>),
+    Bytes(&'a [u8]),
+}
+
+/// The orientation of a decoded `Symbol`.
+///
+/// Unlike `ZBarOrientation` this is available regardless of the ZBar build in use, so code
+/// handling orientations does not depend on the `zbar_fork` feature.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Orientation {
+    Unknown,
+    Up,
+    Right,
+    Down,
+    Left,
+}
+#[cfg(feature = "zbar_fork")]
+impl From<ZBarOrientation> for Orientation {
+    fn from(orientation: ZBarOrientation) -> Self {
+        match orientation {
+            ZBarOrientation::ZBAR_ORIENT_UNKNOWN => Orientation::Unknown,
+            ZBarOrientation::ZBAR_ORIENT_UP      => Orientation::Up,
+            ZBarOrientation::ZBAR_ORIENT_RIGHT   => Orientation::Right,
+            ZBarOrientation::ZBAR_ORIENT_DOWN    => Orientation::Down,
+            ZBarOrientation::ZBAR_ORIENT_LEFT    => Orientation::Left,
+        }
+    }
+}
+
+/// A snapshot of a `ZBarSymbol` that owns its data.
+///
+/// Unlike `ZBarSymbol` it holds no reference to ZBar and can outlive the scanned image. The
+/// decoded data is kept as raw bytes, so binary payloads survive the snapshot unchanged.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnedSymbol {
+    symbol_type: ZBarSymbolType,
+    data: Vec<u8>,
+    quality: i32,
+    points: Vec<(u32, u32)>,
+}
+impl OwnedSymbol {
+    pub fn symbol_type(&self) -> ZBarSymbolType { self.symbol_type }
+    /// Returns the decoded data replacing invalid UTF-8 sequences with
+    /// `U+FFFD REPLACEMENT CHARACTER`.
+    pub fn data(&self) -> Cow<str> { lossy_text(&self.data) }
+    /// Returns the raw decoded data including any NUL bytes.
+    pub fn data_bytes(&self) -> &[u8] { &self.data }
+    pub fn quality(&self) -> i32 { self.quality }
+    pub fn points(&self) -> &[(u32, u32)] { &self.points }
+}
+impl<'a> From<&'a ZBarSymbol> for OwnedSymbol {
+    fn from(symbol: &'a ZBarSymbol) -> Self {
+        OwnedSymbol {
+            symbol_type: symbol.symbol_type(),
+            data: symbol.data_bytes().to_vec(),
+            quality: symbol.quality(),
+            points: symbol.polygon().points(),
+        }
+    }
+}
+impl From<ZBarSymbol> for OwnedSymbol {
+    fn from(symbol: ZBarSymbol) -> Self { (&symbol).into() }
+}
+
 pub struct Polygon {
     symbol: ZBarSymbol
 }
 impl Polygon {
     pub fn point(&self, index: u32) -> Option<(u32, u32)> { self.symbol.loc(index) }
     pub fn iter(&self) -> PolygonIter { self.symbol.clone().into() }
+    /// Collects all points of this `Polygon`.
+    pub fn points(&self) -> Vec<(u32, u32)> { self.iter().collect() }
+    /// Returns the average of all points or `None` if the `Polygon` has no points.
+    pub fn centroid(&self) -> Option<(f64, f64)> {
+        let points = self.points();
+        match points.len() {
+            0 => None,
+            n => {
+                let (x, y) = points
+                    .iter()
+                    .fold((0.0, 0.0), |(x, y), &(px, py)| (x + f64::from(px), y + f64::from(py)));
+                Some((x / n as f64, y / n as f64))
+            }
+        }
+    }
 }
 impl From<ZBarSymbol> for Polygon  {
     fn from(symbol: ZBarSymbol) -> Self { Self { symbol } }
@@ -207,6 +700,45 @@ mod test {
         assert!(symbol.loc_y(4).is_none());
     }
 
+    #[test]
+    fn test_points_iter() {
+        let symbol = create_symbol_en();
+        assert_eq!(
+            symbol.points_iter().collect::<Vec<_>>(),
+            vec![(6, 6), (6, 142), (142, 142), (142, 6)]
+        );
+        assert_eq!(symbol.points_iter().collect::<Vec<_>>(), symbol.polygon().points());
+    }
+
+    #[test]
+    fn test_data_cow() {
+        match create_symbol_en().data_cow() {
+            Cow::Borrowed(data) => assert_eq!(data, "Hello World"),
+            Cow::Owned(_)       => panic!("valid UTF-8 must be borrowed"),
+        }
+        match lossy_text(b"Hello \xffWorld") {
+            Cow::Owned(data) => assert_eq!(data, "Hello \u{fffd}World"),
+            Cow::Borrowed(_) => panic!("invalid UTF-8 must be replaced"),
+        }
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        assert_eq!(create_symbol_en().bounding_box(), Some((6, 6, 136, 136)));
+    }
+
+    #[test]
+    fn test_estimated_module_size() {
+        // version 1 with 21 modules
+        let size = create_symbol_en().estimated_module_size().unwrap();
+        assert!((size - 136.0 / 21.0).abs() < 1e-9);
+        assert!(size > 4.0 && size < 8.0);
+
+        let symbols =
+            create_symbol_set_from_with("test/code128.gif", ZBarSymbolType::ZBAR_CODE128);
+        assert!(symbols.first_symbol().unwrap().estimated_module_size().is_none());
+    }
+
     #[test]
     fn test_loc() {
         let symbol = create_symbol_en();
@@ -218,6 +750,16 @@ mod test {
 
     }
 
+    #[test]
+    fn test_loc_raw() {
+        let symbol = create_symbol_en();
+        (0..symbol.loc_size()).for_each(|i| {
+            let (x, y) = symbol.loc(i).unwrap();
+            assert_eq!(symbol.loc_raw(i).unwrap(), (x as i32, y as i32));
+        });
+        assert!(symbol.loc_raw(symbol.loc_size()).is_none());
+    }
+
     #[test]
     fn test_next() {
         let symbol = create_symbol_multi();
@@ -231,12 +773,234 @@ mod test {
         assert!(create_symbol_multi().components().is_none());
     }
 
+    #[test]
+    fn test_structured_append_parts() {
+        let symbols = create_symbol_set_from_with(
+            "test/qr_structured_append.png",
+            ZBarSymbolType::ZBAR_QRCODE
+        );
+        let symbol = symbols.first_symbol().unwrap();
+        assert_eq!(symbol.data(), "Hello World");
+        assert!(symbol.structured_append_info().is_none());
+
+        let parts = symbol.structured_append_parts().unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].data(), "Hello ");
+        assert_eq!(parts[0].structured_append_info(), Some((0, 2)));
+        assert_eq!(parts[1].data(), "World");
+        assert_eq!(parts[1].clone().structured_append_info(), Some((1, 2)));
+
+        assert!(create_symbol_en().structured_append_parts().is_none());
+    }
+
+    #[test]
+    fn test_reassemble() {
+        let symbols = create_symbol_set_from_with(
+            "test/qr_structured_append.png",
+            ZBarSymbolType::ZBAR_QRCODE
+        );
+        let mut parts = symbols.first_symbol().unwrap().structured_append_parts().unwrap();
+        assert_eq!(reassemble(&parts).unwrap(), "Hello World");
+
+        parts.reverse();
+        assert_eq!(reassemble(&parts).unwrap(), "Hello World");
+
+        // missing part
+        assert!(reassemble(&parts[..1]).is_none());
+        // duplicated part
+        assert!(reassemble(&[parts[0].clone(), parts[0].clone()]).is_none());
+        // not a part
+        assert!(reassemble(&[create_symbol_en()]).is_none());
+        assert!(reassemble(&[]).is_none());
+    }
+
+    #[test]
+    fn test_reassemble_mixed_sequences() {
+        // two sequences of two parts each with different parity
+        let symbols = create_symbol_set_from_with(
+            "test/qr_structured_append_mixed.png",
+            ZBarSymbolType::ZBAR_QRCODE
+        );
+        let mut sequences = symbols
+            .iter()
+            .map(|symbol| symbol.structured_append_parts().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(sequences.len(), 2);
+        sequences.sort_by_key(|parts| parts[0].data().to_owned());
+
+        let (foo_bar, hello_world) = (&sequences[0], &sequences[1]);
+        assert_eq!(reassemble(foo_bar).unwrap(), "Foo Bar");
+        assert_eq!(reassemble(hello_world).unwrap(), "Hello World");
+
+        // same length and complementary indices, but different sequences
+        assert!(reassemble(&[hello_world[0].clone(), foo_bar[1].clone()]).is_none());
+        assert!(reassemble(&[foo_bar[0].clone(), hello_world[1].clone()]).is_none());
+    }
+
     #[test]
     fn test_first_component() {
         // TODO: Better Test
         assert!(create_symbol_multi().first_component().is_none());
     }
 
+    #[test]
+    fn test_validate_checksum() {
+        use prelude::{
+            ZBarImage,
+            ZBarImageScanner
+        };
+
+        let image = ZBarImage::from_path("test/ean13.png").unwrap();
+        let scanner = ZBarImageScanner::builder()
+            .with_config(ZBarSymbolType::ZBAR_EAN13, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .build()
+            .unwrap();
+        let symbol = scanner.scan_image(&image).unwrap().first_symbol().unwrap();
+
+        assert_eq!(symbol.validate_checksum(), Some(true));
+    }
+
+    #[test]
+    fn test_validate_checksum_unsupported() {
+        assert!(create_symbol_en().validate_checksum().is_none());
+    }
+
+    #[test]
+    fn test_is_gtin_checksum_valid() {
+        assert!(is_gtin_checksum_valid("4006381333931"));
+        assert!(is_gtin_checksum_valid("036000291452"));
+        assert!(is_gtin_checksum_valid("96385074"));
+        assert!(!is_gtin_checksum_valid("4006381333932"));
+        assert!(!is_gtin_checksum_valid("4006381X33931"));
+        assert!(!is_gtin_checksum_valid(""));
+    }
+
+    #[test]
+    fn test_gtin14_ean13() {
+        let symbol = create_symbol_set_from_with(
+            "test/ean13.png", ZBarSymbolType::ZBAR_EAN13
+        ).first_symbol().unwrap();
+        assert_eq!(symbol.gtin14(), Some("04006381333931".to_owned()));
+    }
+
+    #[test]
+    fn test_gtin14_upca() {
+        let symbol = create_symbol_set_from_with(
+            "test/upca.png", ZBarSymbolType::ZBAR_UPCA
+        ).first_symbol().unwrap();
+        assert_eq!(symbol.symbol_type(), ZBarSymbolType::ZBAR_UPCA);
+        assert_eq!(symbol.gtin14(), Some("00036000291452".to_owned()));
+    }
+
+    #[test]
+    fn test_gtin14_unsupported() { assert!(create_symbol_en().gtin14().is_none()); }
+
+    #[test]
+    fn test_expand_upce() {
+        assert_eq!(expand_upce("04252614"), "042100005264");
+        assert_eq!(expand_upce("01234565"), "012345000065");
+        assert_eq!(expand_upce("01234133"), "012300000413");
+        assert_eq!(expand_upce("01234144"), "012340000014");
+    }
+
+    #[test]
+    fn test_no_addon() {
+        let symbol = create_symbol_en();
+        assert!(!symbol.is_addon());
+        assert!(symbol.addon_data().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "zbar_fork")]
+    fn test_addon() {
+        use prelude::{
+            ZBarImage,
+            ZBarImageScanner
+        };
+
+        let image = ZBarImage::from_path("test/ean13_addon5.png").unwrap();
+        let scanner = ZBarImageScanner::builder()
+            .with_config(ZBarSymbolType::ZBAR_EAN13, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .with_config(ZBarSymbolType::ZBAR_EAN5, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .build()
+            .unwrap();
+        let symbol = scanner.scan_image(&image).unwrap().first_symbol().unwrap();
+
+        assert!(!symbol.is_addon());
+        assert_eq!(symbol.addon_data(), Some("52495"));
+    }
+
+    #[test]
+    fn test_debug() {
+        let debug = format!("{:?}", create_symbol_en());
+        assert!(debug.contains("ZBAR_QRCODE"));
+        assert!(debug.contains("Hello World"));
+
+        let symbols =
+            create_symbol_set_from_with("test/code128_nul.png", ZBarSymbolType::ZBAR_CODE128);
+        let debug = format!("{:?}", symbols.first_symbol().unwrap());
+        assert!(debug.contains("AB\\0CD"));
+    }
+
+    #[test]
+    fn test_data_bytes() { assert_eq!(create_symbol_en().data_bytes(), b"Hello World"); }
+
+    #[test]
+    fn test_symbol_type_value() {
+        use symbol_type_from_value;
+
+        let value = create_symbol_en().symbol_type_value();
+        assert_eq!(value, 64);
+        assert_eq!(symbol_type_from_value(value), Some(ZBarSymbolType::ZBAR_QRCODE));
+    }
+
+    #[test]
+    fn test_data_len() {
+        assert_eq!(create_symbol_en().data_len(), "Hello World".len());
+
+        // CODE-128 code set A can encode control characters
+        let symbols =
+            create_symbol_set_from_with("test/code128_nul.png", ZBarSymbolType::ZBAR_CODE128);
+        let symbol = symbols.first_symbol().unwrap();
+        assert_eq!(symbol.data(), "AB");
+        assert_eq!(symbol.data_len(), 5);
+        assert_eq!(symbol.data_bytes(), b"AB\0CD");
+    }
+
+    #[test]
+    fn test_data_with() {
+        let symbol = create_symbol_en();
+        assert_eq!(
+            symbol.data_with(TextPolicy::Strict).unwrap(),
+            SymbolData::Text("Hello World".into())
+        );
+        assert_eq!(
+            symbol.data_with(TextPolicy::Bytes).unwrap(),
+            SymbolData::Bytes(b"Hello World")
+        );
+    }
+
+    #[test]
+    fn test_text_policy_strict() {
+        assert!(TextPolicy::Strict.apply(b"Hello \xffWorld").is_err());
+    }
+
+    #[test]
+    fn test_text_policy_lossy() {
+        assert_eq!(
+            TextPolicy::Lossy.apply(b"Hello \xffWorld").unwrap(),
+            SymbolData::Text("Hello \u{fffd}World".into())
+        );
+    }
+
+    #[test]
+    fn test_text_policy_bytes() {
+        assert_eq!(
+            TextPolicy::Bytes.apply(b"Hello \xffWorld").unwrap(),
+            SymbolData::Bytes(b"Hello \xffWorld")
+        );
+    }
+
     #[test]
     fn test_xml() { assert_eq!(create_symbol_en().xml(), XML); }
 
@@ -260,6 +1024,37 @@ mod test {
         assert!(iter.next().is_none());
     }
 
+    #[test]
+    fn test_polygon_points() {
+        assert_eq!(
+            create_symbol_en().polygon().points(),
+            vec![(6, 6), (6, 142), (142, 142), (142, 6)]
+        );
+    }
+
+    #[test]
+    fn test_polygon_centroid() {
+        assert_eq!(create_symbol_en().polygon().centroid().unwrap(), (74.0, 74.0));
+    }
+
+    #[test]
+    fn test_owned_symbol() {
+        let owned = OwnedSymbol::from(create_symbol_en());
+        assert_eq!(owned.symbol_type(), ZBarSymbolType::ZBAR_QRCODE);
+        assert_eq!(owned.data(), "Hello World");
+        assert!(owned.quality() > 0);
+        assert_eq!(owned.points(), &[(6, 6), (6, 142), (142, 142), (142, 6)]);
+    }
+
+    #[test]
+    fn test_owned_symbol_binary() {
+        let symbols =
+            create_symbol_set_from_with("test/code128_nul.png", ZBarSymbolType::ZBAR_CODE128);
+        let owned = OwnedSymbol::from(symbols.first_symbol().unwrap());
+        assert_eq!(owned.data_bytes(), b"AB\0CD");
+        assert_eq!(owned.data(), "AB\0CD");
+    }
+
     #[test]
     #[cfg(feature = "zbar_fork")]
     fn test_configs() {
@@ -274,10 +1069,80 @@ mod test {
         assert_eq!(create_symbol_en().modifiers(), 0);
     }
 
+    #[test]
+    #[cfg(feature = "zbar_fork")]
+    fn test_has_modifier() {
+        let symbol = create_symbol_set_from("test/code128_gs1.png").first_symbol().unwrap();
+        assert!(symbol.has_modifier(ZBarModifier::ZBAR_MOD_GS1));
+        assert!(!symbol.has_modifier(ZBarModifier::ZBAR_MOD_AIM));
+        assert!(!create_symbol_en().has_modifier(ZBarModifier::ZBAR_MOD_GS1));
+    }
+
+    #[test]
+    #[cfg(feature = "zbar_fork")]
+    fn test_modifier_list() {
+        let symbol = create_symbol_set_from("test/code128_gs1.png").first_symbol().unwrap();
+        assert_eq!(symbol.modifier_list(), vec![ZBarModifier::ZBAR_MOD_GS1]);
+        assert!(create_symbol_en().modifier_list().is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "zbar_fork")]
+    fn test_parse_gs1() {
+        let symbol = create_symbol_set_from("test/code128_gs1_batch.png").first_symbol().unwrap();
+        assert_eq!(
+            symbol.parse_gs1().unwrap(),
+            vec![
+                ("01".to_owned(), "04006381333931".to_owned()),
+                ("10".to_owned(), "ABC123".to_owned()),
+            ]
+        );
+        assert!(create_symbol_en().parse_gs1().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "zbar_fork")]
+    fn test_parse_gs1_fields() {
+        assert_eq!(
+            parse_gs1_fields("10ABC\u{1d}3103001250").unwrap(),
+            vec![
+                ("10".to_owned(), "ABC".to_owned()),
+                ("3103".to_owned(), "001250".to_owned()),
+            ]
+        );
+        assert!(parse_gs1_fields("010400638133").is_none());
+        assert!(parse_gs1_fields("5512").is_none());
+        assert!(parse_gs1_fields("").is_none());
+    }
+
     #[test]
     #[cfg(feature = "zbar_fork")]
     fn orientation() {
         assert_eq!(create_symbol_en().orientation(), ZBarOrientation::ZBAR_ORIENT_UP);
+        assert_eq!(create_symbol_en().orientation_kind(), Orientation::Up);
+    }
+
+    #[test]
+    #[cfg(feature = "zbar_fork")]
+    fn test_orientation_from() {
+        assert_eq!(Orientation::from(ZBarOrientation::ZBAR_ORIENT_UNKNOWN), Orientation::Unknown);
+        assert_eq!(Orientation::from(ZBarOrientation::ZBAR_ORIENT_UP), Orientation::Up);
+        assert_eq!(Orientation::from(ZBarOrientation::ZBAR_ORIENT_RIGHT), Orientation::Right);
+        assert_eq!(Orientation::from(ZBarOrientation::ZBAR_ORIENT_DOWN), Orientation::Down);
+        assert_eq!(Orientation::from(ZBarOrientation::ZBAR_ORIENT_LEFT), Orientation::Left);
+    }
+
+    #[test]
+    fn test_eq_hash_by_content() {
+        use std::collections::HashSet;
+
+        let mut set = HashSet::new();
+        set.insert(create_symbol_en());
+        set.insert(create_symbol_en());
+        assert_eq!(set.len(), 1);
+
+        let de = create_symbol_set_from("test/qr_hallo-welt.png").first_symbol().unwrap();
+        assert_ne!(create_symbol_en(), de);
     }
 
     fn create_symbol_en() -> ZBarSymbol {
@@ -288,6 +1153,24 @@ mod test {
         create_symbol_set_from("test/greetings.png").first_symbol().unwrap()
     }
 
+    fn create_symbol_set_from_with(
+        path: impl AsRef<Path>,
+        symbol_type: ZBarSymbolType
+    ) -> ZBarSymbolSet
+    {
+        use prelude::{
+            ZBarImage,
+            ZBarImageScanner
+        };
+
+        let image = ZBarImage::from_path(&path).unwrap();
+        let scanner = ZBarImageScanner::builder()
+            .with_config(symbol_type, ZBarConfig::ZBAR_CFG_ENABLE, 1)
+            .build()
+            .unwrap();
+        scanner.scan_image(&image).unwrap()
+    }
+
     fn create_symbol_set_from(path: impl AsRef<Path>) -> ZBarSymbolSet {
         use prelude::{
             ZBarImage,
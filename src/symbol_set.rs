@@ -1,9 +1,63 @@
 use {
     ffi,
     image,
-    symbol::ZBarSymbol
+    symbol::{
+        OwnedSymbol,
+        ZBarSymbol
+    },
+    symbol_name,
+    ZBarSymbolType,
 };
-use std::mem;
+use std::{
+    collections::{
+        HashMap,
+        HashSet
+    },
+    fmt::{
+        self,
+        Write
+    },
+    mem,
+    str,
+};
+
+/// Snapshots the symbols of all given `ZBarSymbolSet`s and removes duplicates.
+///
+/// Two symbols are considered duplicates if they have the same type and data. The order of
+/// first occurrence is preserved.
+///
+/// # Examples
+///
+/// ```
+/// use zbars::prelude::*;
+/// use zbars::symbol_set::collect_unique;
+///
+/// let scanner = ZBarImageScanner::builder().build().unwrap();
+/// let frames = vec![
+///     ZBarImage::new(1, 1, Y800, vec![0]).unwrap(),
+///     ZBarImage::new(1, 1, Y800, vec![1]).unwrap(),
+/// ];
+/// let symbols = collect_unique(frames.iter().map(|frame| scanner.scan_image(frame).unwrap()));
+/// println!("{} distinct symbols", symbols.len());
+/// ```
+pub fn collect_unique(sets: impl IntoIterator<Item = ZBarSymbolSet>) -> Vec<OwnedSymbol> {
+    let mut seen = HashSet::new();
+    sets.into_iter()
+        .flat_map(|set| set.iter().map(OwnedSymbol::from).collect::<Vec<_>>())
+        .filter(|symbol| seen.insert((symbol.symbol_type(), symbol.data_bytes().to_vec())))
+        .collect()
+}
+
+/// Aggregate statistics of a `ZBarSymbolSet`, see `ZBarSymbolSet::stats`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanStats {
+    /// Number of decoded symbols.
+    pub symbol_count: usize,
+    /// Average quality of all symbols or `0.0` if there are none.
+    pub avg_quality: f64,
+    /// Number of symbols per symbology.
+    pub by_type: HashMap<ZBarSymbolType, usize>,
+}
 
 pub struct ZBarSymbolSet {
     symbol_set: *const ffi::zbar_symbol_set_s,
@@ -51,6 +105,147 @@ impl ZBarSymbolSet {
 
     pub fn iter(&self) -> SymbolIter { self.first_symbol().into() }
 
+    /// Iterates over all `Symbol`s paired with their bounding box as `(x, y, width, height)`.
+    ///
+    /// Symbols without location data are skipped.
+    pub fn with_boxes(&self) -> impl Iterator<Item = (ZBarSymbol, (u32, u32, u32, u32))> {
+        self.iter().filter_map(|symbol| symbol.bounding_box().map(|bounds| (symbol, bounds)))
+    }
+
+    /// Returns the decoded data of all `Symbol`s in the order reported by ZBar.
+    ///
+    /// Invalid UTF-8 is replaced with `U+FFFD REPLACEMENT CHARACTER`.
+    pub fn data_strings(&self) -> Vec<String> {
+        self.iter()
+            .map(|symbol| String::from_utf8_lossy(symbol.data_bytes()).into_owned())
+            .collect()
+    }
+
+    /// Serializes all `Symbol`s to a JSON array without requiring serde.
+    ///
+    /// Each symbol becomes an object with its `type`, `data` and location `points`. If the data
+    /// is not valid UTF-8 it is base64 encoded and an additional `"encoding": "base64"` member
+    /// is added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbars::prelude::*;
+    ///
+    /// let image = ZBarImage::new(1, 1, Y800, vec![0]).unwrap();
+    /// let scanner = ZBarImageScanner::builder().build().unwrap();
+    /// assert_eq!(scanner.scan_image(&image).unwrap().to_json(), "[]");
+    /// ```
+    pub fn to_json(&self) -> String {
+        let symbols = self.iter()
+            .map(|symbol| {
+                let data = match str::from_utf8(symbol.data_bytes()) {
+                    Ok(data) => format!("\"data\":{}", json_string(data)),
+                    Err(_)   => format!(
+                        "\"data\":\"{}\",\"encoding\":\"base64\"",
+                        base64(symbol.data_bytes())
+                    ),
+                };
+                let points = symbol.points_iter()
+                    .map(|(x, y)| format!("[{},{}]", x, y))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!(
+                    "{{\"type\":{},{},\"points\":[{}]}}",
+                    json_string(symbol_name(symbol.symbol_type())), data, points
+                )
+            })
+            .collect::<Vec<_>>();
+        format!("[{}]", symbols.join(","))
+    }
+
+    /// Renders the location of all `Symbol`s as SVG document for visual debugging.
+    ///
+    /// Each symbol becomes a `<polygon>` outlining its location followed by a `<text>` label of
+    /// its decoded data. `width` and `height` should be the dimensions of the scanned image so
+    /// the SVG can be laid over it.
+    pub fn to_svg(&self, width: u32, height: u32) -> String {
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" \
+             viewBox=\"0 0 {0} {1}\">\n",
+            width, height
+        );
+        self.iter().for_each(|symbol| {
+            let points = symbol.points_iter()
+                .map(|(x, y)| format!("{},{}", x, y))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let _ = writeln!(
+                svg,
+                "<polygon points=\"{}\" fill=\"none\" stroke=\"red\"/>",
+                points
+            );
+            if let Some((x, y)) = symbol.points_iter().next() {
+                let _ = writeln!(
+                    svg,
+                    "<text x=\"{}\" y=\"{}\" fill=\"red\">{}</text>",
+                    x, y, xml_escape(&String::from_utf8_lossy(symbol.data_bytes()))
+                );
+            }
+        });
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Returns all `Symbol`s sorted descending by their quality.
+    ///
+    /// Symbols of equal quality keep the order ZBar reported them in.
+    pub fn sorted_by_quality(&self) -> Vec<ZBarSymbol> {
+        let mut symbols = self.iter().collect::<Vec<_>>();
+        symbols.sort_by(|a, b| b.quality().cmp(&a.quality()));
+        symbols
+    }
+
+    /// Returns the `Symbol` with the highest quality or the first of them if qualities tie.
+    ///
+    /// Prefer this over `first_symbol` if exactly one code is expected but several candidates
+    /// may have been decoded.
+    pub fn best_symbol(&self) -> Option<ZBarSymbol> {
+        self.iter().fold(None, |best: Option<ZBarSymbol>, symbol| match best {
+            Some(best) if best.quality() >= symbol.quality() => Some(best),
+            _                                                 => Some(symbol),
+        })
+    }
+
+    /// Returns the symbols of this set that are not in `other`.
+    ///
+    /// Two symbols are considered equal if they have the same type and data. This is useful for
+    /// reporting codes that newly appeared between two video frames without relying on ZBar's
+    /// result cache.
+    pub fn difference(&self, other: &ZBarSymbolSet) -> Vec<OwnedSymbol> {
+        let others = other.iter()
+            .map(|symbol| (symbol.symbol_type(), symbol.data_bytes().to_vec()))
+            .collect::<HashSet<_>>();
+        self.iter()
+            .filter(|symbol| {
+                !others.contains(&(symbol.symbol_type(), symbol.data_bytes().to_vec()))
+            })
+            .map(OwnedSymbol::from)
+            .collect()
+    }
+
+    /// Returns the symbol count, average quality and count per symbology of this set.
+    pub fn stats(&self) -> ScanStats {
+        let mut by_type = HashMap::new();
+        let mut quality_sum = 0i64;
+        let mut symbol_count = 0;
+        for symbol in self.iter() {
+            *by_type.entry(symbol.symbol_type()).or_insert(0) += 1;
+            quality_sum += i64::from(symbol.quality());
+            symbol_count += 1;
+        }
+        let avg_quality = match symbol_count {
+            0 => 0.0,
+            n => quality_sum as f64 / n as f64,
+        };
+        ScanStats { symbol_count, avg_quality, by_type }
+    }
+
     #[cfg(feature = "zbar_fork")]
     pub fn first_symbol_unfiltered(&self) -> Option<ZBarSymbol> {
         ZBarSymbol::from_raw(
@@ -63,10 +258,68 @@ impl Clone for ZBarSymbolSet {
     fn clone(&self) -> Self { Self::from_raw(self.symbol_set, self.image).unwrap() }
 }
 
+impl fmt::Debug for ZBarSymbolSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ZBarSymbolSet").field("size", &self.size()).finish()
+    }
+}
+
 impl Drop for ZBarSymbolSet {
     fn drop(&mut self) { image::set_ref(self.image, -1); }
 }
 
+fn json_string(value: &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"'  => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => { let _ = write!(json, "\\u{:04x}", c as u32); }
+            c    => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+fn xml_escape(value: &str) -> String {
+    value.chars().fold(String::with_capacity(value.len()), |mut xml, c| {
+        match c {
+            '&'  => xml.push_str("&amp;"),
+            '<'  => xml.push_str("&lt;"),
+            '>'  => xml.push_str("&gt;"),
+            '"'  => xml.push_str("&quot;"),
+            '\'' => xml.push_str("&apos;"),
+            c    => xml.push(c),
+        }
+        xml
+    })
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity((bytes.len() + 2) / 3 * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, byte)| n | u32::from(*byte) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
 pub struct SymbolIter {
     symbol: Option<ZBarSymbol>,
 }
@@ -111,6 +364,152 @@ mod test {
         assert_eq!(create_symbol_set().first_symbol_unfiltered().unwrap().data(), "Hello World");
     }
 
+    #[test]
+    fn test_data_strings() {
+        assert_eq!(create_symbol_set().data_strings(), vec!["Hello World", "Hallo Welt"]);
+    }
+
+    #[test]
+    fn test_with_boxes() {
+        let symbols = create_symbol_from("test/qr_hello-world.png").symbols().unwrap();
+        let boxes = symbols.with_boxes().collect::<Vec<_>>();
+        assert_eq!(boxes.len(), 1);
+        assert_eq!(boxes[0].0.data(), "Hello World");
+        assert_eq!(boxes[0].1, (6, 6, 136, 136));
+    }
+
+    #[test]
+    fn test_to_json() {
+        let json = create_symbol_from("test/qr_hello-world.png").symbols().unwrap().to_json();
+        assert_eq!(
+            json,
+            "[{\"type\":\"QR-Code\",\"data\":\"Hello World\",\
+             \"points\":[[6,6],[6,142],[142,142],[142,6]]}]"
+        );
+    }
+
+    #[test]
+    fn test_to_svg() {
+        let svg = create_symbol_from("test/qr_hello-world.png").symbols().unwrap().to_svg(150, 150);
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains("width=\"150\" height=\"150\""));
+        assert!(svg.contains("<polygon points=\"6,6 6,142 142,142 142,6\""));
+        assert!(svg.contains(">Hello World</text>"));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn test_xml_escape() {
+        assert_eq!(
+            xml_escape("<a href='x'>&\"</a>"),
+            "&lt;a href=&apos;x&apos;&gt;&amp;&quot;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("a\"b\\c\nd\u{1}"), "\"a\\\"b\\\\c\\nd\\u0001\"");
+    }
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(&[0xff, 0xfe]), "//4=");
+    }
+
+    #[test]
+    fn test_debug() {
+        assert!(format!("{:?}", create_symbol_set()).contains("size: 2"));
+    }
+
+    #[test]
+    fn test_sorted_by_quality() {
+        // the taller a barcode, the more scan lines decode it
+        let symbols = create_symbol_from("test/code128_qualities.png")
+            .symbols()
+            .unwrap()
+            .sorted_by_quality();
+        let data = symbols.iter().map(|symbol| symbol.data().to_owned()).collect::<Vec<_>>();
+        assert_eq!(data, vec!["Tall", "Mid", "Short"]);
+        assert!(symbols[0].quality() > symbols[1].quality());
+        assert!(symbols[1].quality() > symbols[2].quality());
+
+        // equal qualities keep ZBar's order
+        let symbols = create_symbol_set().sorted_by_quality();
+        assert_eq!(symbols[0].quality(), symbols[1].quality());
+        assert_eq!(symbols[0].data(), "Hello World");
+        assert_eq!(symbols[1].data(), "Hallo Welt");
+    }
+
+    #[test]
+    fn test_collect_unique() {
+        let symbols = collect_unique(vec![
+            create_symbol_set(),
+            create_symbol_from("test/qr_hello-world.png").symbols().unwrap(),
+        ]);
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[0].data(), "Hello World");
+        assert_eq!(symbols[1].data(), "Hallo Welt");
+    }
+
+    #[test]
+    fn test_collect_unique_binary() {
+        let create_nul_set = || create_symbol_from("test/code128_nul.png").symbols().unwrap();
+        let symbols = collect_unique(vec![create_nul_set(), create_nul_set()]);
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].data_bytes(), b"AB\0CD");
+    }
+
+    #[test]
+    fn test_best_symbol() {
+        let symbols = create_symbol_from("test/code128_qualities.png").symbols().unwrap();
+        assert_eq!(symbols.best_symbol().unwrap().data(), "Tall");
+
+        // the first one wins on equal qualities
+        let symbols = create_symbol_set();
+        let best = symbols.best_symbol().unwrap();
+        assert!(symbols.iter().all(|symbol| symbol.quality() == best.quality()));
+        assert_eq!(best.data(), "Hello World");
+
+        let image = ZBarImage::new(1, 1, Y800, vec![0]).unwrap();
+        let scanner = ZBarImageScanner::builder().build().unwrap();
+        assert!(scanner.scan_image(&image).unwrap().best_symbol().is_none());
+    }
+
+    #[test]
+    fn test_difference() {
+        let greetings = create_symbol_set();
+        let hello = create_symbol_from("test/qr_hello-world.png").symbols().unwrap();
+
+        let difference = greetings.difference(&hello);
+        assert_eq!(difference.len(), 1);
+        assert_eq!(difference[0].symbol_type(), ZBarSymbolType::ZBAR_QRCODE);
+        assert_eq!(difference[0].data(), "Hallo Welt");
+
+        assert!(hello.difference(&greetings).is_empty());
+    }
+
+    #[test]
+    fn test_stats() {
+        let symbols = create_symbol_set();
+        let stats = symbols.stats();
+        assert_eq!(stats.symbol_count, 2);
+        assert_eq!(stats.by_type.len(), 1);
+        assert_eq!(stats.by_type[&ZBarSymbolType::ZBAR_QRCODE], 2);
+        let qualities = symbols.iter().map(|symbol| symbol.quality()).collect::<Vec<_>>();
+        assert_eq!(stats.avg_quality, f64::from(qualities[0] + qualities[1]) / 2.0);
+
+        let image = ZBarImage::new(1, 1, Y800, vec![0]).unwrap();
+        let scanner = ZBarImageScanner::builder().build().unwrap();
+        let stats = scanner.scan_image(&image).unwrap().stats();
+        assert_eq!(stats.symbol_count, 0);
+        assert_eq!(stats.avg_quality, 0.0);
+        assert!(stats.by_type.is_empty());
+    }
+
     fn create_symbol_set() -> ZBarSymbolSet {
         create_symbol_from("test/greetings.png").symbols().unwrap()
     }